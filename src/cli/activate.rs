@@ -0,0 +1,36 @@
+use std::fs;
+use std::path::Path;
+
+use heros_spirit_utils::rom::RomReader;
+use heros_spirit_utils::Result;
+
+use super::{export::parse_rom, helpers, locate};
+
+/// Points "rom.txt" of the game installation at `rom`, which has to be in its "Roms/" folder
+pub fn use_rom(rom: &Path) -> Result<()> {
+    let game_dir = locate::game_dir().ok_or("failed to find the game installation")?;
+    let roms_dir = game_dir.join("Roms");
+
+    let mut reader = RomReader::open(rom.to_path_buf())?;
+    let parsed = parse_rom(&mut reader);
+    if parsed.maps.is_err() || parsed.draw_data().is_none() {
+        Err(format!("failed to parse \"{}\"", rom.display()))?;
+    }
+
+    let rom = fs::canonicalize(rom)?;
+    if rom.parent() != Some(&fs::canonicalize(&roms_dir)?) {
+        Err(format!(
+            "\"{}\" is not in \"{}\", the game can't load it",
+            rom.display(),
+            roms_dir.display()
+        ))?;
+    }
+    let name = rom.file_name().unwrap_or_default().to_string_lossy();
+
+    let rom_txt = game_dir.join("rom.txt");
+    let previous = helpers::read_to_string(&rom_txt)?;
+    helpers::write(&rom_txt, name.as_bytes())?;
+    eprintln!("Switched the active rom from {} to {name}", previous.trim());
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use image::{imageops, Rgba, RgbaImage};
+use imageproc::{drawing::draw_hollow_rect_mut, rect::Rect};
+use itertools::Itertools;
+
+use heros_spirit_utils::{
+    map::{self, diff_maps, Difference, Map},
+    rom::RomReader,
+    Result,
+};
+
+use super::{
+    export::{parse_rom, save_map_image},
+    helpers::{self, ResultExtension},
+};
+
+/// Space between the two versions of a map in the diff images
+const GAP: u32 = 16;
+
+/// Writes the report and images into `dir`
+pub fn diff_roms(first_path: PathBuf, second_path: PathBuf, dir: &Path) -> Result<()> {
+    let mut reader = RomReader::open(first_path.clone())?;
+    let first = parse_rom(&mut reader);
+    let mut reader = RomReader::open(second_path.clone())?;
+    let second = parse_rom(&mut reader);
+
+    let first_maps = first
+        .maps
+        .as_ref()
+        .map_err(|_| "failed to parse first maps")?;
+    let second_maps = second
+        .maps
+        .as_ref()
+        .map_err(|_| "failed to parse second maps")?;
+
+    let differences = diff_maps(first_maps, second_maps)
+        .into_iter()
+        .into_group_map_by(Difference::map)
+        .into_iter()
+        .sorted_unstable_by_key(|(map, _)| *map)
+        .collect::<Vec<_>>();
+
+    let mut report = format!(
+        "First: {}\nSecond: {}\n",
+        first_path.display(),
+        second_path.display()
+    );
+    for (map, differences) in &differences {
+        writeln!(report, "\n{} ({map}):", map::map_name(*map))?;
+        for difference in differences {
+            writeln!(report, "  {difference}")?;
+        }
+    }
+
+    let count = differences
+        .iter()
+        .map(|(_, differences)| differences.len())
+        .sum::<usize>();
+    eprintln!("{count} differences in {} maps", differences.len());
+    helpers::write(dir.join("diff.txt"), report).feedback("Write diff report");
+
+    let (Some(first_data), Some(second_data)) = (first.draw_data(), second.draw_data()) else {
+        return Ok(());
+    };
+
+    for (map, differences) in &differences {
+        let (Some(first_map), Some(second_map)) =
+            (find_map(first_maps, *map), find_map(second_maps, *map))
+        else {
+            continue;
+        };
+
+        let first_image = first_data.draw_map(first_map);
+        let second_image = second_data.draw_map(second_map);
+        let mut image = side_by_side(&first_image, &second_image);
+
+        for id in differences.iter().filter_map(Difference::id) {
+            for x_offset in [0, first_image.width() + GAP] {
+                highlight_cell(&mut image, id.x as u32 * 16 + x_offset, id.y as u32 * 16);
+            }
+        }
+
+        let name = map::map_name(*map);
+        save_map_image(dir.join("diff"), *map, &image).feedback(format!("Save {name} diff"));
+    }
+
+    Ok(())
+}
+
+fn find_map(maps: &[Map], identifier: u8) -> Option<&Map> {
+    maps.iter().find(|map| map.identifier == identifier)
+}
+
+fn side_by_side(first: &RgbaImage, second: &RgbaImage) -> RgbaImage {
+    let mut image = RgbaImage::new(
+        first.width() + GAP + second.width(),
+        first.height().max(second.height()),
+    );
+    imageops::overlay(&mut image, first, 0, 0);
+    imageops::overlay(&mut image, second, i64::from(first.width() + GAP), 0);
+
+    image
+}
+
+fn highlight_cell(image: &mut RgbaImage, x: u32, y: u32) {
+    for inset in 0..2 {
+        draw_hollow_rect_mut(
+            image,
+            Rect::at((x + inset) as i32, (y + inset) as i32)
+                .of_size(16 - 2 * inset, 16 - 2 * inset),
+            Rgba::from([255, 0, 0, 255]),
+        );
+    }
+}
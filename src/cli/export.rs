@@ -6,136 +6,156 @@ use image::ImageFormat;
 use image::RgbaImage;
 use itertools::Itertools;
 
-use crate::graphics::{merge_maps, DrawData};
-use crate::helpers::ResultExtension;
-use crate::map::{self, Collectible, Enemy, Map};
-use crate::rom::{ArchiveReader, Rom, RomReader};
-use crate::saves::Saves;
-use crate::{helpers, Result};
+use heros_spirit_utils::graphics::{merge_maps, DrawData};
+use heros_spirit_utils::map::{self, Collectible, Enemy, Map};
+use heros_spirit_utils::rom::{ArchiveReader, Rom, RomReader};
+use heros_spirit_utils::saves::Saves;
+use heros_spirit_utils::Result;
+
+use super::helpers::{self, skip, ResultExtension};
 
 #[allow(clippy::similar_names)]
-pub fn export_all(rom: PathBuf) {
-    let saves = Saves::decode();
+pub fn export_all(rom: PathBuf, dir: &Path, save_dir: &Path) {
+    let saves = export_saves(save_dir);
+
+    if let Some(rom) = export_rom(rom, dir) {
+        export_missing_items(&rom, &saves, &save_dir.join("completion"));
+    }
+}
 
-    if let Some(rom) = export_rom(rom) {
-        rom.export_missing_items(&saves);
+pub fn export_saves(save_dir: &Path) -> Saves {
+    let saves = Saves::decode(save_dir, &save_dir.join("completion"));
+
+    for (name, save) in [
+        ("savedata", &saves.savedata),
+        ("savedatb", &saves.savedatb),
+        ("savedatc", &saves.savedatc),
+        ("bunny", &saves.bunny),
+        ("hardcore", &saves.hardcore),
+    ] {
+        match save {
+            Some(save) => save.as_ref().feedback(format!("Export {name}")),
+            None => skip(format!("Export {name}"), "no save file"),
+        }
+    }
+    match &saves.global {
+        Some(global) => global.as_ref().feedback("Export global save"),
+        None => skip("Export global save", "no save file"),
     }
+
+    saves
 }
 
-pub fn export_rom(rom: PathBuf) -> Option<Rom> {
-    let mut reader = RomReader::open(rom)?;
-    let rom = Rom::parse(&mut reader);
+pub fn export_rom(rom: PathBuf, dir: &Path) -> Option<Rom> {
+    let mut reader = RomReader::open(rom).ok_feedback("Read rom")?;
+    let rom = parse_rom(&mut reader);
 
-    rom.export(&mut reader);
-    rom.export_extras();
+    export_archive(&rom, &mut reader, dir);
+    export_extras(&rom, dir);
 
     Some(rom)
 }
 
-impl Rom {
-    pub fn draw_data(&self) -> Option<DrawData> {
-        if let (Some(tile_data), Some(map_colors), Some(map_meta)) =
-            (&self.tile_data, &self.map_colors, &self.map_meta)
-        {
-            Some(DrawData {
-                tile_data,
-                map_colors,
-                map_meta,
-            })
-        } else {
-            None
-        }
-    }
+/// Parses `reader` and reports how each part of the rom went
+pub(super) fn parse_rom(reader: &mut RomReader) -> Rom {
+    let rom = Rom::parse(reader);
 
-    pub fn export(&self, reader: &mut RomReader) {
-        if let Some(data) = self.draw_data() {
-            export_tilesets(&data).feedback("Export graphics");
+    rom.tile_data.as_ref().feedback("Parse graphics");
+    rom.maps.as_ref().feedback("Parse maps");
+    rom.map_colors.as_ref().feedback("Parse map colors");
+    rom.map_meta.as_ref().feedback("Parse map meta");
 
-            if let Some(maps) = &self.maps {
-                export_maps("rom_files/Maps", maps, &data).feedback("Export maps");
-            }
-        }
+    rom
+}
 
-        if let Some(index) = reader.index.map_colors {
-            export_file("rom_files", &mut reader.archive, index).feedback("Export map colors");
+pub(super) fn export_archive(rom: &Rom, reader: &mut RomReader, dir: &Path) {
+    if let Some(data) = rom.draw_data() {
+        export_tilesets(&data, dir).feedback("Export graphics");
+
+        if let Ok(maps) = &rom.maps {
+            export_maps(dir.join("Maps"), maps, &data).feedback("Export maps");
         }
+    }
+
+    if let Some(index) = reader.index.map_colors {
+        export_file(dir, &mut reader.archive, index).feedback("Export map colors");
+    }
 
-        export_files("rom_files", &mut reader.archive, &reader.index.map_meta)
-            .feedback("Export map meta");
+    export_files(dir, &mut reader.archive, &reader.index.map_meta).feedback("Export map meta");
 
-        export_files(
-            "rom_files/Textures",
-            &mut reader.archive,
-            &reader.index.images,
-        )
-        .feedback("Export images");
+    export_files(
+        &dir.join("Textures"),
+        &mut reader.archive,
+        &reader.index.images,
+    )
+    .feedback("Export images");
 
-        export_files("rom_files", &mut reader.archive, &reader.index.audio)
-            .feedback("Export sounds");
+    export_files(dir, &mut reader.archive, &reader.index.audio).feedback("Export sounds");
 
-        export_files(
-            "rom_files/Shaders",
-            &mut reader.archive,
-            &reader.index.shaders,
-        )
-        .feedback("Export shaders");
+    export_files(
+        &dir.join("Shaders"),
+        &mut reader.archive,
+        &reader.index.shaders,
+    )
+    .feedback("Export shaders");
 
-        export_files("rom_files/Other", &mut reader.archive, &reader.index.other)
-            .feedback("Export other files");
-    }
+    export_files(&dir.join("Other"), &mut reader.archive, &reader.index.other)
+        .feedback("Export other files");
+}
 
-    pub fn export_extras(&self) {
-        if let Some(maps) = &self.maps {
-            export_stats(maps).feedback("Gather stats");
+fn export_extras(rom: &Rom, dir: &Path) {
+    if let Ok(maps) = &rom.maps {
+        export_stats(maps, dir).feedback("Gather stats");
 
-            if let Some(data) = self.draw_data() {
-                export_maps("rom_files/Maps", maps, &data).feedback("Export maps");
+        if let Some(data) = rom.draw_data() {
+            export_maps(dir.join("Maps"), maps, &data).feedback("Export maps");
+            let images_dir = dir.join("Maps/images");
 
-                let maps = maps
-                    .iter()
-                    .map(|map| {
-                        let identifier = map.identifier;
-                        let map = data.draw_map(map);
+            let maps = maps
+                .iter()
+                .map(|map| {
+                    let identifier = map.identifier;
+                    let map = data.draw_map(map);
 
-                        export_map_image(identifier, &map)
-                            .feedback(format!("Draw map {}", map::map_name(identifier)));
+                    save_map_image(&images_dir, identifier, &map)
+                        .feedback(format!("Draw map {}", map::map_name(identifier)));
 
-                        (identifier, map)
-                    })
-                    .collect();
+                    (identifier, map)
+                })
+                .collect();
 
-                for (name, map) in merge_maps(maps) {
-                    export_image(name, &map).feedback(format!("Draw {name}"));
-                }
+            for (name, map) in merge_maps(maps) {
+                save_image(&images_dir, name, &map).feedback(format!("Draw {name}"));
             }
         }
     }
+}
 
-    pub fn export_missing_items(&self, saves: &Saves) {
-        if let Some(maps) = &self.maps {
-            for (name, save) in [
-                ("savedata", &saves.savedata),
-                ("savedatb", &saves.savedatb),
-                ("savedatc", &saves.savedatc),
-                ("bunny", &saves.bunny),
-                ("hcp", &saves.hardcore),
-            ] {
-                if let Some(save) = save {
-                    save.check(name, maps)
-                        .feedback(format!("Check missing items from {name}"));
-                }
+fn export_missing_items(rom: &Rom, saves: &Saves, completion_dir: &Path) {
+    if let Ok(maps) = &rom.maps {
+        for (name, save) in [
+            ("savedata", &saves.savedata),
+            ("savedatb", &saves.savedatb),
+            ("savedatc", &saves.savedatc),
+            ("bunny", &saves.bunny),
+            ("hcp", &saves.hardcore),
+        ] {
+            if let Some(Ok(save)) = save {
+                save.check(name, maps, completion_dir)
+                    .feedback(format!("Check missing items from {name}"));
             }
         }
     }
 }
 
-fn export_tilesets(data: &DrawData) -> Result<()> {
-    helpers::create_dir_all("rom_files/Graphics/tile16")?;
-    data.draw_tile8s("rom_files/Graphics/tile8.bmp")?;
-    data.draw_tile16s()
+fn export_tilesets(data: &DrawData, dir: &Path) -> Result<()> {
+    let tile16_dir = dir.join("Graphics/tile16");
+    helpers::create_dir_all(&tile16_dir)?;
+    data.draw_tile8s(dir.join("Graphics/tile8.bmp"))?;
+    data.draw_tile16s(tile16_dir)
 }
 
-fn export_files(path: &str, archive: &mut ArchiveReader, indices: &[usize]) -> Result<()> {
+fn export_files(path: &Path, archive: &mut ArchiveReader, indices: &[usize]) -> Result<()> {
     for index in indices {
         export_file(path, archive, *index)?;
     }
@@ -143,13 +163,12 @@ fn export_files(path: &str, archive: &mut ArchiveReader, indices: &[usize]) -> R
     Ok(())
 }
 
-fn export_file(path: &str, archive: &mut ArchiveReader, index: usize) -> Result<()> {
+fn export_file(path: &Path, archive: &mut ArchiveReader, index: usize) -> Result<()> {
     let mut reader = archive.by_index(index)?;
     let name = reader
         .enclosed_name()
         .ok_or_else(|| format!("Failed to sanitize filename \"{}\"", reader.name()))?;
-    let mut file_path = PathBuf::from(path);
-    file_path.push(name);
+    let file_path = path.join(name);
     let mut writer = helpers::file_create(file_path)?;
     io::copy(&mut reader, &mut writer)?;
 
@@ -181,10 +200,6 @@ pub(super) fn save_map_image<P: AsRef<Path>>(
     save_map_image(folder.as_ref(), identifier, map)
 }
 
-fn export_map_image(identifier: u8, map: &RgbaImage) -> Result<()> {
-    save_map_image("rom_files/Maps/images", identifier, map)
-}
-
 pub(super) fn save_image<P1: AsRef<Path>, P2: AsRef<Path>>(
     folder: P1,
     name: P2,
@@ -203,11 +218,7 @@ pub(super) fn save_image<P1: AsRef<Path>, P2: AsRef<Path>>(
     save_image(folder.as_ref(), name.as_ref(), map)
 }
 
-fn export_image<P: AsRef<Path>>(name: P, map: &RgbaImage) -> Result<()> {
-    save_image("rom_files/Maps/images", name, map)
-}
-
-fn export_stats(maps: &[Map]) -> Result<()> {
+fn export_stats(maps: &[Map], dir: &Path) -> Result<()> {
     let sprite_stats = maps
         .iter()
         .map(|map| (map.identifier, map.stats()))
@@ -263,8 +274,9 @@ fn export_stats(maps: &[Map]) -> Result<()> {
     let collectibles_stats = format!("{collectibles_header}\n{collectibles_stats}");
     let enemies_stats = format!("{enemies_header}\n{enemies_stats}");
 
-    helpers::write("rom_files/Maps/stats/collectibles.csv", collectibles_stats)?;
-    helpers::write("rom_files/Maps/stats/enemies.csv", enemies_stats)?;
+    let stats_dir = dir.join("Maps/stats");
+    helpers::write(stats_dir.join("collectibles.csv"), collectibles_stats)?;
+    helpers::write(stats_dir.join("enemies.csv"), enemies_stats)?;
 
     Ok(())
 }
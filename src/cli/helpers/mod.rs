@@ -0,0 +1,6 @@
+mod result;
+
+pub use heros_spirit_utils::fs::{
+    create_dir_all, file_create, files_in_dir, read, read_dir, read_to_string, write,
+};
+pub use result::{skip, take_steps, ResultExtension, Status, Step};
@@ -0,0 +1,87 @@
+use std::fmt::Display;
+use std::mem;
+use std::sync::{Mutex, PoisonError};
+
+use serde::Serialize;
+
+static STEPS: Mutex<Vec<Step>> = Mutex::new(Vec::new());
+
+/// Outcome of a step reported through [`ResultExtension`] or [`skip`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Step {
+    pub description: String,
+    pub status: Status,
+    /// The error of a failed step, or why a step was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Success,
+    Failure,
+    Skipped,
+}
+
+/// Returns the steps reported so far and forgets them
+pub fn take_steps() -> Vec<Step> {
+    mem::take(&mut *STEPS.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Reports a step that wasn't needed, which doesn't count as a failure
+pub fn skip<D: Display, R: Display>(description: D, reason: R) {
+    eprintln!("{description} - Skipped: {reason}");
+    record(
+        description.to_string(),
+        Status::Skipped,
+        Some(reason.to_string()),
+    );
+}
+
+fn record(description: String, status: Status, message: Option<String>) {
+    STEPS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Step {
+            description,
+            status,
+            message,
+        });
+}
+
+pub trait ResultExtension: Sized {
+    type Output;
+
+    fn ok_feedback<D: Display>(self, description: D) -> Option<Self::Output>;
+
+    fn feedback<D: Display>(self, description: D) {
+        self.ok_feedback(description);
+    }
+}
+
+impl<T, E> ResultExtension for Result<T, E>
+where
+    E: Display,
+{
+    type Output = T;
+
+    fn ok_feedback<D: Display>(self, description: D) -> Option<Self::Output> {
+        match self {
+            Ok(t) => {
+                eprintln!("{description} - Success");
+                record(description.to_string(), Status::Success, None);
+                Some(t)
+            }
+            Err(err) => {
+                eprintln!("{description} - Failure: {err}");
+                record(
+                    description.to_string(),
+                    Status::Failure,
+                    Some(err.to_string()),
+                );
+                None
+            }
+        }
+    }
+}
@@ -2,91 +2,100 @@ use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 
-use crate::helpers::ResultExtension;
-use crate::map::Map;
-use crate::rom::RomWriter;
-use crate::{graphics, helpers, saves, Result};
+use heros_spirit_utils::map::Map;
+use heros_spirit_utils::rom::RomWriter;
+use heros_spirit_utils::{graphics, saves, Result};
 
-pub fn import_all(rom: PathBuf) {
-    import_saves();
-    import_rom(rom);
+use super::helpers::{self, skip, ResultExtension};
+
+pub fn import_all(rom: PathBuf, dir: &Path, save_dir: &Path) {
+    import_saves(save_dir);
+    import_rom(rom, dir);
 }
 
-pub fn import_saves() {
-    saves::encode("savedata").feedback("Import savedata");
-    saves::encode("savedatb").feedback("Import savedatb");
-    saves::encode("savedatc").feedback("Import savedatc");
-    saves::encode("bunny").feedback("Import bunny");
-    saves::encode("hcp").feedback("Import hardcore");
+pub fn import_saves(save_dir: &Path) {
+    for (name, file) in [
+        ("savedata", "savedata"),
+        ("savedatb", "savedatb"),
+        ("savedatc", "savedatc"),
+        ("bunny", "bunny"),
+        ("hardcore", "hcp"),
+    ] {
+        let path = save_dir.join(file);
+        // Only saves that were exported have anything to import
+        if path.with_extension("json").is_file() {
+            saves::encode(path).feedback(format!("Import {name}"));
+        } else {
+            skip(format!("Import {name}"), "no exported save");
+        }
+    }
 }
 
-pub fn import_rom(rom: PathBuf) {
+pub fn import_rom(rom: PathBuf, dir: &Path) {
     if let Some(mut rom) = RomWriter::create(rom).ok_feedback("Create rom") {
-        rom.import_tilesets().feedback("Import graphics");
-        rom.import_maps().feedback("Import maps");
-        rom.import_files("Maps/Metadata", "Maps/Metadata/")
+        import_tilesets(&mut rom, dir).feedback("Import graphics");
+        import_maps(&mut rom, dir).feedback("Import maps");
+        import_files(&mut rom, &dir.join("Maps/Metadata"), "Maps/Metadata/")
             .feedback("Import map meta");
-        rom.import_files("Textures", "").feedback("Import images");
-        rom.import_files("Audio", "Audio/")
-            .feedback("Import sounds");
-        rom.import_files("Shaders", "").feedback("Import shaders");
-        rom.import_files("Other", "").feedback("Import other files");
+        import_files(&mut rom, &dir.join("Textures"), "").feedback("Import images");
+        import_files(&mut rom, &dir.join("Audio"), "Audio/").feedback("Import sounds");
+        import_files(&mut rom, &dir.join("Shaders"), "").feedback("Import shaders");
+        import_files(&mut rom, &dir.join("Other"), "").feedback("Import other files");
     }
 }
 
-impl RomWriter {
-    fn import_tilesets(&mut self) -> Result<()> {
-        let tile8_list = graphics::undraw_tile8s("rom_files/Graphics/tile8.bmp")?;
-        self.write("graphics.bin", &graphics::encode_graphics(tile8_list))?;
+fn import_tilesets(rom: &mut RomWriter, dir: &Path) -> Result<()> {
+    let tile8_list = graphics::undraw_tile8s(dir.join("Graphics/tile8.bmp"))?;
+    rom.write("graphics.bin", &graphics::encode_graphics(tile8_list))?;
 
-        Ok(())
-    }
-
-    fn import_maps(&mut self) -> Result<()> {
-        for file in helpers::read_dir("rom_files/Maps")? {
-            let file = file?;
-            let path = file.path();
-            if path.extension() != Some(OsStr::new("tmx")) {
-                continue;
-            }
-            let name = path.file_stem().unwrap();
-            let name = name
-                .to_str()
-                .ok_or_else(|| format!("invalid filename \"{}\"", path.display()))?;
+    Ok(())
+}
 
-            let identifier = name
-                .strip_prefix("map")
-                .and_then(|id| id.parse().ok())
-                .ok_or_else(|| format!("invalid map identifier \"{name}\""))?;
-            let map = Map::from_tmx(identifier, &helpers::read_to_string(&path)?)?;
-            self.write(&format!("Maps/{name}"), &map.encode())?;
+fn import_maps(rom: &mut RomWriter, dir: &Path) -> Result<()> {
+    for file in helpers::read_dir(dir.join("Maps"))? {
+        let file = file?;
+        let path = file.path();
+        if path.extension() != Some(OsStr::new("tmx")) {
+            continue;
         }
+        let name = path.file_stem().unwrap();
+        let name = name
+            .to_str()
+            .ok_or_else(|| format!("invalid filename \"{}\"", path.display()))?;
 
-        Ok(())
+        let identifier = name
+            .strip_prefix("map")
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| format!("invalid map identifier \"{name}\""))?;
+        let map = Map::from_tmx(identifier, &helpers::read_to_string(&path)?)?;
+        rom.write(&format!("Maps/{name}"), &map.encode())?;
     }
 
-    fn import_files(&mut self, folder: &str, prefix: &str) -> Result<()> {
-        for file in helpers::read_dir(format!("rom_files/{folder}"))? {
-            let file = file?;
-            let file_name = file.file_name();
-            let file_name = file_name
-                .to_str()
-                .ok_or_else(|| format!("invalid filename \"{}\"", file_name.to_string_lossy()))?;
+    Ok(())
+}
 
-            if file.file_type()?.is_dir() {
-                self.import_files(
-                    &format!("{folder}/{file_name}"),
-                    &format!("{prefix}{file_name}/"),
-                )?;
-            } else {
-                self.import_file(file.path(), &format!("{prefix}{file_name}"))?;
-            }
-        }
+fn import_files(rom: &mut RomWriter, folder: &Path, prefix: &str) -> Result<()> {
+    for file in helpers::read_dir(folder)? {
+        let file = file?;
+        let file_name = file.file_name();
+        let file_name = file_name
+            .to_str()
+            .ok_or_else(|| format!("invalid filename \"{}\"", file_name.to_string_lossy()))?;
 
-        Ok(())
+        if file.file_type()?.is_dir() {
+            import_files(
+                rom,
+                &folder.join(file_name),
+                &format!("{prefix}{file_name}/"),
+            )?;
+        } else {
+            import_file(rom, file.path(), &format!("{prefix}{file_name}"))?;
+        }
     }
 
-    fn import_file<P: AsRef<Path> + Debug>(&mut self, path: P, name: &str) -> Result<()> {
-        self.write(name, &helpers::read(path)?)
-    }
+    Ok(())
+}
+
+fn import_file<P: AsRef<Path> + Debug>(rom: &mut RomWriter, path: P, name: &str) -> Result<()> {
+    rom.write(name, &helpers::read(path)?)
 }
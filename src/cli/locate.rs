@@ -0,0 +1,199 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
+/// Overrides the game installation folder
+const GAME_DIR_VAR: &str = "HEROS_SPIRIT_DIR";
+/// Overrides the save folder
+const SAVE_DIR_VAR: &str = "HEROS_SPIRIT_SAVES";
+
+const STEAM_GAME_DIR: &str = "steamapps/common/Hero's Spirit";
+const SAVE_NAMES: [&str; 6] = ["savedata", "savedatb", "savedatc", "bunny", "hcp", "global"];
+
+/// Finds the game installation, a folder containing "Roms/" and "rom.txt"
+///
+/// Looks in the current directory, next to the executable and in the Steam libraries.
+pub fn game_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(GAME_DIR_VAR) {
+        return Some(dir.into());
+    }
+
+    let current_dir = env::current_dir().ok();
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let steam_dirs = steam_libraries()
+        .into_iter()
+        .map(|library| library.join(STEAM_GAME_DIR));
+
+    current_dir
+        .into_iter()
+        .chain(exe_dir)
+        .chain(steam_dirs)
+        .find(|dir| is_game_dir(dir))
+}
+
+/// The "Roms/" folder of the game installation, or of the current directory if there is none
+pub fn roms_dir() -> PathBuf {
+    game_dir().map_or_else(|| "Roms".into(), |dir| dir.join("Roms"))
+}
+
+/// Finds the folder containing the save files
+///
+/// The current directory is used if it contains saves, otherwise the game's folder in local app data.
+/// On Linux, local app data is looked for in the Proton prefixes of the Steam libraries and the Wine prefix.
+pub fn save_dir() -> PathBuf {
+    if let Some(dir) = env::var_os(SAVE_DIR_VAR) {
+        return dir.into();
+    }
+
+    let current_dir = PathBuf::from(".");
+    if is_save_dir(&current_dir) {
+        return current_dir;
+    }
+
+    local_app_data_dirs()
+        .iter()
+        .find_map(|dir| game_save_dir(dir))
+        .unwrap_or(current_dir)
+}
+
+fn is_game_dir(dir: &Path) -> bool {
+    dir.join("Roms").is_dir() && dir.join("rom.txt").is_file()
+}
+
+fn is_save_dir(dir: &Path) -> bool {
+    SAVE_NAMES.iter().any(|name| dir.join(name).is_file())
+}
+
+/// The subfolder of local app data named after the game which contains saves
+fn game_save_dir(local_app_data: &Path) -> Option<PathBuf> {
+    fs::read_dir(local_app_data)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|dir| {
+            let name = dir.file_name().unwrap_or_default().to_string_lossy();
+            name.to_lowercase().contains("hero") && is_save_dir(dir)
+        })
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn steam_libraries() -> Vec<PathBuf> {
+    let mut roots = vec![];
+
+    if cfg!(windows) {
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            if let Some(dir) = env::var_os(var) {
+                roots.push(PathBuf::from(dir).join("Steam"));
+            }
+        }
+    } else if let Some(home) = home_dir() {
+        if cfg!(target_os = "macos") {
+            roots.push(home.join("Library/Application Support/Steam"));
+        } else {
+            roots.push(home.join(".steam/steam"));
+            roots.push(home.join(".local/share/Steam"));
+            roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        }
+    }
+
+    let mut libraries = vec![];
+    for root in roots.into_iter().filter(|root| root.is_dir()) {
+        if let Ok(folders) = fs::read_to_string(root.join("steamapps/libraryfolders.vdf")) {
+            libraries.extend(library_paths(&folders));
+        }
+        libraries.push(root);
+    }
+
+    libraries.into_iter().unique().collect()
+}
+
+/// Reads the `"path"` entries of Steam's "libraryfolders.vdf"
+fn library_paths(folders: &str) -> Vec<PathBuf> {
+    folders
+        .lines()
+        .filter_map(|line| {
+            let value = line.trim().strip_prefix("\"path\"")?.trim();
+            let value = value.strip_prefix('"')?.strip_suffix('"')?;
+            Some(PathBuf::from(value.replace("\\\\", "\\")))
+        })
+        .collect()
+}
+
+fn local_app_data_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+
+    if cfg!(windows) {
+        dirs.extend(env::var_os("LOCALAPPDATA").map(PathBuf::from));
+    } else if cfg!(target_os = "linux") {
+        for library in steam_libraries() {
+            let Ok(prefixes) = fs::read_dir(library.join("steamapps/compatdata")) else {
+                continue;
+            };
+            dirs.extend(prefixes.flatten().map(|prefix| {
+                prefix
+                    .path()
+                    .join("pfx/drive_c/users/steamuser/AppData/Local")
+            }));
+        }
+
+        let wine_prefix = env::var_os("WINEPREFIX")
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(".wine")));
+        if let Some(Ok(users)) =
+            wine_prefix.map(|prefix| fs::read_dir(prefix.join("drive_c/users")))
+        {
+            dirs.extend(
+                users
+                    .flatten()
+                    .map(|user| user.path().join("AppData/Local")),
+            );
+        }
+    }
+
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steam_library_folders() {
+        let folders = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"0"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+	}
+}
+"#;
+
+        assert_eq!(
+            library_paths(folders),
+            [
+                PathBuf::from("C:\\Program Files (x86)\\Steam"),
+                PathBuf::from("/mnt/games/SteamLibrary"),
+            ]
+        );
+    }
+}
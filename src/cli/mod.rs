@@ -1,10 +1,20 @@
+mod activate;
+mod diff;
 mod export;
+mod helpers;
 mod import;
+mod locate;
+mod option;
 mod randomize;
+mod report;
 
-pub use export::{export_all, export_rom};
+pub use activate::use_rom;
+pub use diff::diff_roms;
+pub use export::{export_all, export_rom, export_saves};
 pub use import::{import_all, import_rom, import_saves};
-pub use randomize::{draw_logic, randomize};
+pub use option::{disable_prompts, OptionExtension};
+pub use randomize::{draw_logic, rando_stats, randomize, recover_spoiler, validate_logic};
+pub use report::report;
 
 use std::fmt::{self, Display};
 use std::fs::DirEntry;
@@ -14,10 +24,22 @@ use clap::{Args, Parser, Subcommand};
 use inquire::Select;
 use strum::{Display, EnumDiscriminants, VariantArray};
 
-use crate::helpers::files_in_dir;
-use crate::Result;
+use heros_spirit_utils::rando::{GameMode, TrickDifficulty};
+use heros_spirit_utils::Result;
+
+use helpers::files_in_dir;
+
+const DEFAULT_EXPORT_DIR: &str = "rom_files";
+const DEFAULT_OUTPUT: &str = "randomizer.hsrom";
+const DEFAULT_SPOILER: &str = "rando/spoiler.yaml";
+const DEFAULT_RECOVERED_SPOILER: &str = "rando/recovered_spoiler.yaml";
+const DEFAULT_VANILLA: &str = "main.hsrom";
+const DEFAULT_RANDO_DIR: &str = "rando";
 
 pub trait FromPrompt: Sized {
+    /// What is prompted for, used when prompts are disabled
+    const NAME: &'static str;
+
     fn from_prompt() -> Result<Self>;
 }
 
@@ -26,6 +48,12 @@ pub trait FromPrompt: Sized {
 pub struct Cli {
     #[command(subcommand)]
     pub action: Option<Action>,
+    /// Fail instead of prompting for anything that wasn't passed as an argument
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+    /// Print a report of every step and whether it succeeded to stdout as json
+    #[arg(long, global = true)]
+    pub json: bool,
 }
 
 #[derive(Subcommand, EnumDiscriminants)]
@@ -33,54 +61,115 @@ pub struct Cli {
 pub enum Action {
     /// Randomize transfer destinations and item locations
     ///
-    /// Roms are looked for in a "Roms/" subfolder.
-    /// Additionally the file "rando/logic.json" is required.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
+    /// Additionally the file "rando/logic.json" is required,
+    /// or "rando/logic_main.json" for the main campaign, unless another one is passed with --logic.
+    /// Settings are read from "rando/settings.yaml" if it exists.
     Randomize {
         #[command(flatten)]
         args: RandomizeArgs,
     },
+    /// Change which rom the game loads by editing "rom.txt"
+    ///
+    /// Roms are looked for in the "Roms/" folder of the game installation.
+    /// Only roms that can be read are accepted.
+    UseRom {
+        #[command(flatten)]
+        args: RomArgs,
+    },
+    /// Generate many seeds and report how they turned out
+    ///
+    /// Reports the failure rate, retries, sphere counts and required areas into "stats.txt",
+    /// and draws a heatmap of the locations of every piece of gear into "stats/", both inside "rando/" by default.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
+    /// Settings are read from "rando/settings.yaml" if it exists.
+    RandoStats {
+        #[command(flatten)]
+        args: StatsArgs,
+    },
+    /// Reconstruct the spoiler of a randomized rom
+    ///
+    /// Compares the rom to the vanilla one and writes the placements and transfers it finds
+    /// into "rando/recovered_spoiler.txt" and "rando/recovered_spoiler.yaml" by default.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
+    RecoverSpoiler {
+        #[command(flatten)]
+        args: RecoverArgs,
+    },
     /// Visualize the contents of "rando/logic.json"
     ///
-    /// Roms are looked for in a "Roms/" subfolder.
+    /// The images are saved into "visualizer/", inside "rando/" by default.
+    /// The start position of a seed is outlined in green, pass its spoiler with --spoiler
+    /// or its start area with --start.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
     DrawLogic {
         #[command(flatten)]
-        args: RomArgs,
+        args: DrawLogicArgs,
+    },
+    /// Check the logic file for mistakes
+    ///
+    /// Every problem is reported with its area and id. Exits with an error if any are found.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
+    ValidateLogic {
+        #[command(flatten)]
+        args: LogicArgs,
+    },
+    /// Compare the maps of two roms, such as two seeds
+    ///
+    /// Lists every changed tile, sprite and transfer destination in "diff.txt",
+    /// and draws both versions of every changed map side by side into "diff/", both inside "rando/" by default.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
+    DiffRoms {
+        #[command(flatten)]
+        args: DiffArgs,
     },
     /// Exports the rom and save files into formats suitable for viewing and editing.
     ///
     /// If it can find both the rom and save files, it can compare the two to determine which items you're missing.
     ///
-    /// Roms are looked for in a "Roms/" subfolder, Saves are looked for in the current directory.
+    /// Roms are looked for in the "Roms/" folder of the game installation, Saves are looked for in the save folder.
     Export {
         #[command(flatten)]
-        args: RomArgs,
+        args: ExportArgs,
+        #[command(flatten)]
+        save_args: SaveArgs,
     },
     /// Reimport all files previously exported
     Import {
         #[command(flatten)]
-        args: RomArgs,
+        args: ExportArgs,
+        #[command(flatten)]
+        save_args: SaveArgs,
     },
     /// Exports save files into formats suitable for viewing and editing.
     ///
-    /// Saves are looked for in the current directory.
-    ExportSaves,
+    /// Saves are looked for in the save folder.
+    ExportSaves {
+        #[command(flatten)]
+        args: SaveArgs,
+    },
     /// Reimport the files previously exported with "Export Saves"
-    ImportSaves,
+    ImportSaves {
+        #[command(flatten)]
+        args: SaveArgs,
+    },
     /// Exports the rom into formats suitable for viewing and editing.
     ///
-    /// Roms are looked for in a "Roms/" subfolder.
+    /// Roms are looked for in the "Roms/" folder of the game installation.
     ExportRom {
         #[command(flatten)]
-        args: RomArgs,
+        args: ExportArgs,
     },
     /// Reimport the files previously exported with "Export Rom"
     ImportRom {
         #[command(flatten)]
-        args: RomArgs,
+        args: ExportArgs,
     },
 }
 
 impl FromPrompt for Action {
+    const NAME: &'static str = "action";
+
     fn from_prompt() -> Result<Self> {
         let action =
             Select::new("Select an action", ActionDiscriminants::VARIANTS.to_vec()).prompt()?;
@@ -89,22 +178,43 @@ impl FromPrompt for Action {
             ActionDiscriminants::Randomize => Action::Randomize {
                 args: RandomizeArgs::default(),
             },
-            ActionDiscriminants::DrawLogic => Action::DrawLogic {
+            ActionDiscriminants::UseRom => Action::UseRom {
                 args: RomArgs::default(),
             },
+            ActionDiscriminants::RandoStats => Action::RandoStats {
+                args: StatsArgs::default(),
+            },
+            ActionDiscriminants::RecoverSpoiler => Action::RecoverSpoiler {
+                args: RecoverArgs::default(),
+            },
+            ActionDiscriminants::DrawLogic => Action::DrawLogic {
+                args: DrawLogicArgs::default(),
+            },
+            ActionDiscriminants::ValidateLogic => Action::ValidateLogic {
+                args: LogicArgs::default(),
+            },
+            ActionDiscriminants::DiffRoms => Action::DiffRoms {
+                args: DiffArgs::default(),
+            },
             ActionDiscriminants::Export => Action::Export {
-                args: RomArgs::default(),
+                args: ExportArgs::default(),
+                save_args: SaveArgs::default(),
             },
             ActionDiscriminants::Import => Action::Import {
-                args: RomArgs::default(),
+                args: ExportArgs::default(),
+                save_args: SaveArgs::default(),
+            },
+            ActionDiscriminants::ExportSaves => Action::ExportSaves {
+                args: SaveArgs::default(),
+            },
+            ActionDiscriminants::ImportSaves => Action::ImportSaves {
+                args: SaveArgs::default(),
             },
-            ActionDiscriminants::ExportSaves => Action::ExportSaves,
-            ActionDiscriminants::ImportSaves => Action::ImportSaves,
             ActionDiscriminants::ExportRom => Action::ExportRom {
-                args: RomArgs::default(),
+                args: ExportArgs::default(),
             },
             ActionDiscriminants::ImportRom => Action::ImportRom {
-                args: RomArgs::default(),
+                args: ExportArgs::default(),
             },
         };
 
@@ -119,6 +229,165 @@ pub struct RandomizeArgs {
     /// Using the same seed will result in the same output
     #[arg(long, short)]
     pub seed: Option<String>,
+    /// Settings file (yaml or json) choosing what to shuffle
+    #[arg(long)]
+    pub settings: Option<PathBuf>,
+    /// Game mode to randomize, overrides the settings file
+    #[arg(long, short)]
+    pub mode: Option<GameMode>,
+    /// Logic file to use instead of the default one for the game mode
+    #[arg(long)]
+    pub logic: Option<PathBuf>,
+    /// Share code from a previous spoiler, reproduces its settings and seed
+    #[arg(long, conflicts_with_all = ["seed", "settings", "mode", "trick_difficulty", "tricks"])]
+    pub code: Option<String>,
+    /// Enable every trick in the logic up to this difficulty, overrides the settings file
+    #[arg(long)]
+    pub trick_difficulty: Option<TrickDifficulty>,
+    /// Enable a trick from the logic by name, in addition to those in the settings file
+    #[arg(long = "trick", value_name = "TRICK")]
+    pub tricks: Vec<String>,
+    /// Write this many hints about gear locations and dead ends into "hints.txt" next to the spoiler
+    #[arg(long)]
+    pub hints: Option<usize>,
+    /// Edit "rom.txt" so the game loads the randomized rom
+    #[arg(long)]
+    pub activate: bool,
+    #[command(flatten)]
+    pub output_args: OutputArgs,
+    /// Saves to edit for starting items and a moved starting position
+    #[command(flatten)]
+    pub save_args: SaveArgs,
+}
+
+#[derive(Args)]
+pub struct OutputArgs {
+    /// Where to write the randomized rom, defaults to "randomizer.hsrom" in the "Roms/" folder
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// Where to write the spoiler yaml, a readable version is written next to it as ".txt"
+    #[arg(long, default_value = DEFAULT_SPOILER)]
+    pub spoiler: PathBuf,
+}
+
+impl Default for OutputArgs {
+    fn default() -> Self {
+        Self {
+            output: None,
+            spoiler: DEFAULT_SPOILER.into(),
+        }
+    }
+}
+
+#[derive(Args, Default)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub rom_args: RomArgs,
+    /// Settings file (yaml or json) choosing what to shuffle
+    #[arg(long)]
+    pub settings: Option<PathBuf>,
+    /// Game mode to randomize, overrides the settings file
+    #[arg(long, short)]
+    pub mode: Option<GameMode>,
+    /// Logic file to use instead of the default one for the game mode
+    #[arg(long)]
+    pub logic: Option<PathBuf>,
+    /// Number of seeds to generate
+    #[arg(long, default_value_t = 100)]
+    pub seeds: usize,
+    #[command(flatten)]
+    pub dir_args: OutputDirArgs,
+}
+
+#[derive(Args)]
+pub struct RecoverArgs {
+    /// Randomized rom to recover the spoiler of
+    #[command(flatten)]
+    pub rom_args: RomArgs,
+    /// Vanilla rom to compare against, defaults to "main.hsrom" in the "Roms/" folder
+    #[arg(long)]
+    pub vanilla: Option<PathBuf>,
+    /// Settings file the rom was generated with, only needed for settings the rom doesn't reflect,
+    /// such as tricks or starting items
+    #[arg(long)]
+    pub settings: Option<PathBuf>,
+    /// Game mode that was randomized, overrides the settings file
+    #[arg(long, short)]
+    pub mode: Option<GameMode>,
+    /// Logic file to use instead of the default one for the game mode
+    #[arg(long)]
+    pub logic: Option<PathBuf>,
+    /// Area the seed starts in if it used a random start, as listed in its spoiler
+    #[arg(long)]
+    pub start: Option<String>,
+    /// Where to write the spoiler yaml, a readable version is written next to it as ".txt"
+    #[arg(long, default_value = DEFAULT_RECOVERED_SPOILER)]
+    pub spoiler: PathBuf,
+}
+
+impl Default for RecoverArgs {
+    fn default() -> Self {
+        Self {
+            rom_args: RomArgs::default(),
+            vanilla: None,
+            settings: None,
+            mode: None,
+            logic: None,
+            start: None,
+            spoiler: DEFAULT_RECOVERED_SPOILER.into(),
+        }
+    }
+}
+
+#[derive(Args, Default)]
+pub struct LogicArgs {
+    #[command(flatten)]
+    pub rom_args: RomArgs,
+    /// Game mode whose logic to use
+    #[arg(long, short, default_value = "hardcore")]
+    pub mode: GameMode,
+    /// Logic file to use instead of the default one for the game mode
+    #[arg(long)]
+    pub logic: Option<PathBuf>,
+}
+
+#[derive(Args, Default)]
+pub struct DrawLogicArgs {
+    #[command(flatten)]
+    pub logic_args: LogicArgs,
+    /// Outline the start position of this area
+    #[arg(long, conflicts_with = "spoiler")]
+    pub start: Option<String>,
+    /// Outline the start position of the seed with this spoiler yaml
+    #[arg(long)]
+    pub spoiler: Option<PathBuf>,
+    #[command(flatten)]
+    pub dir_args: OutputDirArgs,
+}
+
+#[derive(Args, Default)]
+pub struct DiffArgs {
+    /// Rom to compare from
+    pub first: Option<PathBuf>,
+    /// Rom to compare to
+    pub second: Option<PathBuf>,
+    #[command(flatten)]
+    pub dir_args: OutputDirArgs,
+}
+
+#[derive(Args)]
+pub struct OutputDirArgs {
+    /// Folder the output is written into
+    #[arg(long, default_value = DEFAULT_RANDO_DIR)]
+    pub dir: PathBuf,
+}
+
+impl Default for OutputDirArgs {
+    fn default() -> Self {
+        Self {
+            dir: DEFAULT_RANDO_DIR.into(),
+        }
+    }
 }
 
 #[derive(Args, Default)]
@@ -128,9 +397,43 @@ pub struct RomArgs {
     pub rom: Option<PathBuf>,
 }
 
+#[derive(Args)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub rom_args: RomArgs,
+    /// Folder the rom's files are exported into and imported from
+    #[arg(long, default_value = DEFAULT_EXPORT_DIR)]
+    pub dir: PathBuf,
+}
+
+impl Default for ExportArgs {
+    fn default() -> Self {
+        Self {
+            rom_args: RomArgs::default(),
+            dir: DEFAULT_EXPORT_DIR.into(),
+        }
+    }
+}
+
+#[derive(Args, Default)]
+pub struct SaveArgs {
+    /// Folder containing the save files, exported saves and completion info are written next to them.
+    /// Defaults to the current directory if it contains saves, otherwise the game's save folder
+    #[arg(long)]
+    pub saves: Option<PathBuf>,
+}
+
+impl SaveArgs {
+    pub fn dir(&self) -> PathBuf {
+        self.saves.clone().unwrap_or_else(locate::save_dir)
+    }
+}
+
 impl FromPrompt for PathBuf {
+    const NAME: &'static str = "rom";
+
     fn from_prompt() -> Result<Self> {
-        let available_roms = files_in_dir("Roms")?.collect::<Vec<_>>();
+        let available_roms = files_in_dir(locate::roms_dir())?.collect::<Vec<_>>();
         let rom = match available_roms.len() {
             0 => Err("no roms found")?,
             1 => available_roms[0].path(),
@@ -154,17 +457,20 @@ impl Display for DirEntryDisplay {
 
 #[cfg(test)]
 mod tests {
-    use crate::rom::{Rom, RomReader};
+    use std::path::Path;
+
+    use heros_spirit_utils::rom::{Rom, RomReader};
 
-    use super::*;
+    use super::{export::export_archive, *};
 
     #[test]
     fn roundtrip() {
         let mut reader = RomReader::open("Roms/main.hsrom".into()).unwrap();
         let first = Rom::parse(&mut reader);
-        first.export(&mut reader);
+        let dir = Path::new(DEFAULT_EXPORT_DIR);
+        export_archive(&first, &mut reader, dir);
 
-        import_rom("Roms/test.hsrom".into());
+        import_rom("Roms/test.hsrom".into(), dir);
 
         let mut reader = RomReader::open("Roms/test.hsrom".into()).unwrap();
         let second = Rom::parse(&mut reader);
@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use heros_spirit_utils::Result;
+
+use super::FromPrompt;
+
+static PROMPTS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Makes `unwrap_or_prompt` fail instead of prompting
+pub fn disable_prompts() {
+    PROMPTS_DISABLED.store(true, Ordering::Relaxed);
+}
+
+pub trait OptionExtension {
+    type Value;
+
+    fn unwrap_or_prompt(self) -> Result<Self::Value>;
+}
+
+impl<T: FromPrompt> OptionExtension for Option<T> {
+    type Value = T;
+
+    fn unwrap_or_prompt(self) -> Result<Self::Value> {
+        match self {
+            None if PROMPTS_DISABLED.load(Ordering::Relaxed) => Err(format!(
+                "no {} given, prompting for it is disabled by --non-interactive",
+                T::NAME
+            ))?,
+            None => T::from_prompt(),
+            Some(t) => Ok(t),
+        }
+    }
+}
@@ -1,48 +1,197 @@
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    result::Result as StdResult,
+};
+
+use image::RgbaImage;
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::{json, Value};
 
-use crate::{
+use heros_spirit_utils::{
     graphics::merge_maps,
-    helpers::{self, OptionExtension, ResultExtension},
-    map::{self, Map},
-    rando::{generate, Logic, Spoiler, Visualizer},
+    map::{self, Map, MapColors, MapMeta},
+    rando::{
+        self, generate_attempts, hints, parse_share_code, shuffle_cosmetics, Cosmetics, GameMode,
+        Hint, Logic, Settings, Spoiler, StartSpoiler, Stats, Visualizer,
+    },
     rom::{Index, Rom, RomReader, RomWriter},
+    saves::{self, Direction, Position},
     Result,
 };
 
 use super::{
-    export::{save_image, save_map_image},
-    RandomizeArgs,
+    export::{parse_rom, save_image, save_map_image},
+    helpers::{self, skip, ResultExtension},
+    locate, use_rom, DrawLogicArgs, OptionExtension, RandomizeArgs, RecoverArgs, StatsArgs,
+    DEFAULT_OUTPUT, DEFAULT_VANILLA,
 };
 
+const DEFAULT_SETTINGS: &str = "rando/settings.yaml";
+
 pub fn randomize(args: RandomizeArgs) -> Result<()> {
     let rom = args.rom_args.rom.unwrap_or_prompt()?;
-    let logic = Logic::parse().ok_feedback("Parse logic");
+    let (settings, seed, shared) = match args.code {
+        Some(code) => {
+            let shared = parse_share_code(&code)?;
+            (
+                shared.settings.clone(),
+                Some(shared.seed.clone()),
+                Some(shared),
+            )
+        }
+        None => {
+            let mut settings = read_settings(args.settings, args.mode)?;
+            if let Some(difficulty) = args.trick_difficulty {
+                settings.tricks.difficulty = Some(difficulty);
+            }
+            settings.tricks.enabled.extend(args.tricks);
+            (settings, args.seed, None)
+        }
+    };
+    let logic = logic_path(args.logic, settings.mode)
+        .and_then(|path| Logic::parse(path, settings.mode))
+        .ok_feedback("Parse logic");
+    let output = args
+        .output_args
+        .output
+        .unwrap_or_else(|| locate::roms_dir().join(DEFAULT_OUTPUT));
+    let save = args.save_args.dir().join(settings.mode.save_name());
 
-    let reader = RomReader::open(rom);
+    let reader = RomReader::open(rom).ok_feedback("Read rom");
     if let Some(mut reader) = reader {
-        let mut rom = Rom::parse(&mut reader);
+        let Rom {
+            maps,
+            map_colors,
+            map_meta,
+            ..
+        } = parse_rom(&mut reader);
+
+        if let (Some(mut logic), Ok(maps)) = (logic, maps) {
+            if let Some(shared) = &shared {
+                shared.check_logic(&logic)?;
+            }
+
+            let (mut maps, other) = maps
+                .into_iter()
+                .partition::<Vec<_>, _>(|map| settings.mode.contains(map));
+
+            logic.purge_doors(&maps)?;
+
+            let attempts = generate_attempts(&maps, &logic, &settings, seed);
+            for (attempt, err) in attempts.failures.iter().enumerate() {
+                eprintln!("Seed attempt {} failed - {err}", attempt + 1);
+            }
+            let (seed, spoiler) = attempts.result?;
+            seed.apply(&mut maps)?;
+
+            let map_meta = if settings.cosmetics == Cosmetics::default() {
+                None
+            } else {
+                shuffled_cosmetics(map_meta, &map_colors, &maps, &settings, &spoiler.seed)
+                    .ok_feedback("Shuffle cosmetics")
+            };
+
+            let written = write_seed(
+                maps.iter().chain(&other),
+                map_meta.as_ref(),
+                reader,
+                &output,
+            )
+            .ok_feedback("Write seed");
+            write_spoiler(&spoiler, &args.output_args.spoiler).feedback("Write spoiler");
+            eprintln!("Share code: {}", spoiler.code);
 
-        if let (Some(mut logic), Some(maps)) = (logic, rom.maps.take()) {
-            let (mut maps, other) = maps.into_iter().partition::<Vec<_>, _>(Map::is_hardcore);
+            if let Some(count) = args.hints {
+                write_hints(&hints(&spoiler, &logic, count), &args.output_args.spoiler)
+                    .feedback("Write hints");
+            }
 
-            logic.purge_doors(&maps);
+            let edits_save = !settings.plando.starting_items.is_empty() || seed.start.is_some();
+            if edits_save && backup_save(&save).ok_feedback("Back up save").is_some() {
+                if !settings.plando.starting_items.is_empty() {
+                    grant_starting_items(&save, &settings).feedback("Grant starting items");
+                }
 
-            let (seed, spoiler) = generate(&maps, &logic, args.seed)?;
-            seed.apply(&mut maps);
+                if let Some(start) = seed.start {
+                    move_start(&save, start.map, start.x, start.y)
+                        .feedback("Move starting position");
+                }
+            }
 
-            write_seed(maps.iter().chain(&other), reader).feedback("Write seed");
-            write_spoiler(&spoiler).feedback("Write spoiler");
+            if args.activate && written.is_some() {
+                use_rom(&output).feedback("Activate rom");
+            }
         }
     }
 
     Ok(())
 }
 
-fn write_seed<'a, I>(maps: I, mut reader: RomReader) -> Result<()>
+/// Falls back to the default logic file of the game mode
+fn logic_path(path: Option<PathBuf>, mode: GameMode) -> Result<PathBuf> {
+    if let Some(path) = path {
+        return Ok(path);
+    }
+
+    let path = PathBuf::from(match mode {
+        GameMode::Hardcore => "rando/logic.json",
+        GameMode::Main => "rando/logic_main.json",
+    });
+    if !path.exists() {
+        Err(format!(
+            "no logic for {mode:?} mode, \"{}\" doesn't exist and no other file was passed with --logic",
+            path.display()
+        ))?;
+    }
+
+    Ok(path)
+}
+
+fn read_settings(path: Option<PathBuf>, mode: Option<GameMode>) -> Result<Settings> {
+    let mut settings = match path {
+        Some(path) => Settings::parse(path)?,
+        None if Path::new(DEFAULT_SETTINGS).exists() => Settings::parse(DEFAULT_SETTINGS)?,
+        None => Settings::default(),
+    };
+    if let Some(mode) = mode {
+        settings.mode = mode;
+    }
+
+    Ok(settings)
+}
+
+fn shuffled_cosmetics(
+    map_meta: StdResult<HashMap<usize, MapMeta>, String>,
+    map_colors: &StdResult<MapColors, String>,
+    maps: &[Map],
+    settings: &Settings,
+    seed: &str,
+) -> Result<HashMap<usize, MapMeta>> {
+    let mut map_meta = map_meta.map_err(|_| "failed to parse map meta")?;
+    let palettes = map_colors
+        .as_ref()
+        .map_err(|_| "failed to parse map colors")?
+        .map_colors
+        .len();
+
+    shuffle_cosmetics(&mut map_meta, palettes, maps, settings.cosmetics, seed)?;
+
+    Ok(map_meta)
+}
+
+/// `map_meta` replaces the metadata files from the original rom, if given
+fn write_seed<'a, I>(
+    maps: I,
+    map_meta: Option<&HashMap<usize, MapMeta>>,
+    mut reader: RomReader,
+    output: &Path,
+) -> Result<()>
 where
     I: IntoIterator<Item = &'a Map>,
 {
-    let mut writer = RomWriter::create("Roms/randomizer.hsrom".into())?;
+    let mut writer = RomWriter::create(output.to_path_buf())?;
 
     for map in maps {
         writer.write(&format!("Maps/map{:02}", map.identifier), &map.encode())?;
@@ -52,17 +201,29 @@ where
         graphics,
         maps: _,
         map_colors,
-        map_meta,
+        map_meta: map_meta_indices,
         images,
         audio,
         shaders,
         other,
     } = reader.index;
 
+    for index in map_meta_indices {
+        let name = reader.archive.name_for_index(index).unwrap_or_default();
+        if let Some(meta) = map_meta.and_then(|map_meta| {
+            let id = MapMeta::identifier(name).ok()?;
+            map_meta.get(&id)
+        }) {
+            writer.write(name, &serde_json::to_vec(meta)?)?;
+        } else {
+            let file = reader.archive.by_index_raw(index)?;
+            writer.archive.raw_copy_file(file)?;
+        }
+    }
+
     for index in graphics
         .into_iter()
         .chain(map_colors)
-        .chain(map_meta)
         .chain(images)
         .chain(audio)
         .chain(shaders)
@@ -75,31 +236,221 @@ where
     Ok(())
 }
 
-fn write_spoiler(spoiler: &Spoiler) -> Result<()> {
+/// Writes the readable spoiler next to the yaml one
+fn write_spoiler(spoiler: &Spoiler, path: &Path) -> Result<()> {
     let yaml = serde_yaml::to_string(spoiler)?;
-    helpers::write("rando/spoiler.yaml", yaml)?;
+    helpers::write(path, yaml)?;
+    helpers::write(path.with_extension("txt"), spoiler.to_string())?;
+
+    Ok(())
+}
+
+pub fn recover_spoiler(args: RecoverArgs) -> Result<()> {
+    let rom = args.rom_args.rom.unwrap_or_prompt()?;
+    let mut settings = match args.settings {
+        Some(path) => Settings::parse(path)?,
+        None => Settings::default(),
+    };
+    if let Some(mode) = args.mode {
+        settings.mode = mode;
+    }
+    let mut logic = Logic::parse(logic_path(args.logic, settings.mode)?, settings.mode)?;
+
+    let vanilla = args
+        .vanilla
+        .unwrap_or_else(|| locate::roms_dir().join(DEFAULT_VANILLA));
+    let mut reader = RomReader::open(vanilla)?;
+    let mut maps = Map::parse_all(&mut reader)?;
+    let mut reader = RomReader::open(rom)?;
+    let mut randomized = Map::parse_all(&mut reader)?;
+
+    maps.retain(|map| settings.mode.contains(map));
+    randomized.retain(|map| settings.mode.contains(map));
+    logic.purge_doors(&maps)?;
+
+    let spoiler =
+        rando::recover_spoiler(&maps, &randomized, &logic, &settings, args.start.as_deref())?;
+
+    let yaml = serde_yaml::to_string(&spoiler)?;
+    helpers::write(&args.spoiler, yaml).feedback("Write spoiler yaml");
+    helpers::write(args.spoiler.with_extension("txt"), spoiler.to_string())
+        .feedback("Write spoiler");
+
+    Ok(())
+}
+
+/// Writes the hints into "hints.txt" next to the spoiler
+///
+/// They don't go into the rom, none of the files sorted by [`Index`] hold text the game displays
+fn write_hints(hints: &[Hint], spoiler: &Path) -> Result<()> {
+    let sheet = hints
+        .iter()
+        .map(|hint| format!("{hint}\n"))
+        .collect::<String>();
+    helpers::write(spoiler.with_file_name("hints.txt"), sheet)?;
+
+    Ok(())
+}
+
+/// Copies the save to "<name>.bak" the first time randomizing edits it, later runs keep that copy
+fn backup_save(save: &Path) -> Result<()> {
+    let backup = save.with_extension("bak");
+    if !backup.exists() {
+        helpers::write(backup, helpers::read(save)?)?;
+    }
 
     Ok(())
 }
 
-pub fn draw_logic(rom: PathBuf) {
-    let logic = Logic::parse().ok_feedback("Parse logic");
+/// Sets the counts of the starting items, so randomizing again doesn't add them twice
+fn grant_starting_items(save: &Path, settings: &Settings) -> Result<()> {
+    let counts = settings
+        .plando
+        .starting_items
+        .iter()
+        .map(|item| item.0)
+        .counts();
+
+    saves::edit(save, |save| {
+        for (sprite, count) in counts {
+            let count =
+                u8::try_from(count).map_err(|_| format!("can't start with {count} {sprite:?}"))?;
+            save.inventory.set_count(sprite, count)?;
+        }
+
+        Ok(())
+    })
+}
+
+fn move_start(save: &Path, map: u8, x: usize, y: usize) -> Result<()> {
+    saves::edit(save, |save| {
+        save.position = Position {
+            map,
+            x,
+            y,
+            direction: Direction::Down,
+        };
+
+        Ok(())
+    })
+}
+
+/// Returns the stats in a form for the json report
+pub fn rando_stats(args: StatsArgs) -> Result<Value> {
+    let rom = args.rom_args.rom.unwrap_or_prompt()?;
+    let settings = read_settings(args.settings, args.mode)?;
+    let mut logic = Logic::parse(logic_path(args.logic, settings.mode)?, settings.mode)?;
+
+    let mut reader = RomReader::open(rom)?;
+    let rom = parse_rom(&mut reader);
+    let maps = rom.maps.as_ref().map_err(|_| "failed to parse maps")?;
+    let data = rom.draw_data().ok_or("failed to parse graphics")?;
+
+    let maps = maps
+        .iter()
+        .filter(|map| settings.mode.contains(map))
+        .cloned()
+        .collect::<Vec<_>>();
+    logic.purge_doors(&maps)?;
+
+    let stats = Stats::collect(&maps, &logic, &settings, args.seeds)?;
+    eprintln!("\n{stats}");
+    helpers::write(args.dir_args.dir.join("stats.txt"), stats.to_string()).feedback("Write stats");
+
+    let visualizer = Visualizer::new(&logic);
+    for (gear, frequencies) in &stats.gear {
+        let images = maps
+            .iter()
+            .map(|map| {
+                let mut image = data.draw_map(map);
+                visualizer.visualize_frequencies(map.identifier, frequencies, &mut image);
+                (map.identifier, image)
+            })
+            .collect();
+        let (_, merged) = merged_map(images, settings.mode);
+
+        save_image(
+            args.dir_args.dir.join("stats"),
+            format!("{gear:?}.png"),
+            &merged,
+        )
+        .feedback(format!("Save {gear:?} heatmap"));
+    }
+
+    Ok(stats_json(&stats))
+}
+
+fn stats_json(stats: &Stats) -> Value {
+    let failures = stats
+        .failures
+        .iter()
+        .map(|(seed, error)| json!({ "seed": seed, "error": error }))
+        .collect::<Vec<_>>();
+    let required_areas = stats
+        .required_areas
+        .keys()
+        .map(|area| (area, stats.required_area_share(area)))
+        .collect::<BTreeMap<_, _>>();
+
+    json!({
+        "seeds": stats.seeds,
+        "failures": failures,
+        "failure_rate": stats.failure_rate(),
+        "attempts": stats.attempts,
+        "attempt_failure_rate": stats.attempt_failure_rate(),
+        "average_playthrough_spheres": stats.average_playthrough_spheres(),
+        "average_gear_spheres": stats.average_gear_spheres(),
+        "required_areas": required_areas,
+    })
+}
+
+fn merged_map(images: Vec<(u8, RgbaImage)>, mode: GameMode) -> (&'static str, RgbaImage) {
+    let merged_index = match mode {
+        GameMode::Main => 0,
+        GameMode::Hardcore => 1,
+    };
+    merge_maps(images).into_iter().nth(merged_index).unwrap()
+}
+
+pub fn draw_logic(args: DrawLogicArgs) -> Result<()> {
+    let rom = args.logic_args.rom_args.rom.unwrap_or_prompt()?;
+    let mode = args.logic_args.mode;
+    let logic = logic_path(args.logic_args.logic, mode)
+        .and_then(|path| Logic::parse(path, mode))
+        .ok_feedback("Parse logic");
 
-    let reader = RomReader::open(rom);
+    let reader = RomReader::open(rom).ok_feedback("Read rom");
     let Some(mut reader) = reader else {
-        return;
+        return Ok(());
     };
 
-    let rom = Rom::parse(&mut reader);
-    let (Some(logic), Some(maps), Some(data)) = (logic, &rom.maps, rom.draw_data()) else {
-        return;
+    let rom = parse_rom(&mut reader);
+    let (Some(logic), Ok(maps), Some(data)) = (logic, &rom.maps, rom.draw_data()) else {
+        return Ok(());
+    };
+
+    let start = match (args.start, args.spoiler) {
+        (Some(area), _) => {
+            let start = rando::start_positions(maps, &logic)?
+                .into_iter()
+                .find(|(name, _)| **name == area)
+                .map(|(_, position)| position);
+            Some(start.ok_or_else(|| format!("no start position in \"{area}\""))?)
+        }
+        // Seeds without a random start begin at the regular spawn, which has no position to outline
+        (None, Some(spoiler)) => spoiler_start(&spoiler)?.position,
+        (None, None) => None,
     };
 
     let mut images = vec![];
     let visualizer = Visualizer::new(&logic);
+    for area in visualizer.empty_areas() {
+        skip(format!("Draw {area}"), "it has no items or transfers");
+    }
+    let visualizer_dir = args.dir_args.dir.join("visualizer");
 
     for map in maps {
-        if !map.is_hardcore() {
+        if !mode.contains(map) {
             continue;
         }
 
@@ -107,15 +458,57 @@ pub fn draw_logic(rom: PathBuf) {
 
         visualizer.visualize_areas(map.identifier, &mut image);
         visualizer.visualize_connections(map.identifier, &mut image);
+        if let Some(position) = start {
+            visualizer.visualize_start(map.identifier, position, &mut image);
+        }
 
         let name = map::map_name(map.identifier);
-        save_map_image("rando/visualizer", map.identifier, &image)
+        save_map_image(&visualizer_dir, map.identifier, &image)
             .feedback(format!("Save {name} visualization"));
 
         images.push((map.identifier, image));
     }
 
-    let (name, merged) = &merge_maps(images)[1];
+    let (name, merged) = merged_map(images, mode);
+
+    save_image(&visualizer_dir, name, &merged).feedback(format!("Save {name} visualization"));
+
+    Ok(())
+}
+
+/// Reads where the seed of a spoiler starts
+fn spoiler_start(path: &Path) -> Result<StartSpoiler> {
+    #[derive(Deserialize)]
+    struct SpoilerStart {
+        start: StartSpoiler,
+    }
+
+    let spoiler: SpoilerStart = serde_yaml::from_str(&helpers::read_to_string(path)?)?;
+
+    Ok(spoiler.start)
+}
+
+/// Returns the problems found, failing the step if there are any
+pub fn validate_logic(rom: PathBuf, mode: GameMode, logic: Option<PathBuf>) -> Result<Value> {
+    let path = logic_path(logic, mode)?;
+    let mut logic = Logic::parse(&path, mode)?;
+
+    let mut reader = RomReader::open(rom)?;
+    let rom = parse_rom(&mut reader);
+    let mut maps = rom.maps.map_err(|_| "failed to parse maps")?;
+    maps.retain(|map| mode.contains(map));
+
+    let diagnostics = rando::validate_logic(&mut logic, &maps);
+
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let checked = match diagnostics.len() {
+        0 => Ok(()),
+        count => Err(format!("found {count} problems")),
+    };
+    checked.feedback(format!("Validate \"{}\"", path.display()));
 
-    save_image("rando/visualizer", name, merged).feedback(format!("Save {name} visualization"));
+    Ok(serde_json::to_value(&diagnostics)?)
 }
@@ -0,0 +1,50 @@
+use std::process::ExitCode;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use heros_spirit_utils::Result;
+
+use super::helpers::{take_steps, Status, Step};
+
+#[derive(Serialize)]
+struct Report {
+    success: bool,
+    steps: Vec<Step>,
+    /// What the action found, such as logic problems or seed statistics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Reports how the action went, it failed if it returned an error or any of its steps failed
+pub fn report(result: Result<Option<Value>>, json: bool) -> ExitCode {
+    let steps = take_steps();
+    let (output, error) = match result {
+        Ok(output) => (output, None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    let success = error.is_none() && steps.iter().all(|step| step.status != Status::Failure);
+
+    if json {
+        let report = Report {
+            success,
+            steps,
+            output,
+            error,
+        };
+        match serde_json::to_string_pretty(&report) {
+            Ok(report) => println!("{report}"),
+            Err(err) => eprintln!("Error: {err}"),
+        }
+    } else if let Some(error) = error {
+        eprintln!("Error: {error}");
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -80,13 +80,14 @@ impl DrawData<'_> {
         Ok(())
     }
 
-    pub fn draw_tile16s(&self) -> Result<()> {
+    pub fn draw_tile16s(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
         for (index, tile16) in self.tile_data.map_tile16_list.iter().enumerate() {
             let mut image: RgbaImage = ImageBuffer::new(16, 16);
 
             self.draw_tile16(tile16, DEFAULT_PALETTE, &mut image, 0, 0, false);
 
-            let path = format!("rom_files/Graphics/tile16/map_{}.png", index + 1);
+            let path = dir.join(format!("map_{}.png", index + 1));
             image.save_with_format(&path, ImageFormat::Png)?;
         }
         for (index, tile16) in self.tile_data.sprite_tile16_list.iter().enumerate() {
@@ -95,7 +96,7 @@ impl DrawData<'_> {
             let palette = get_sprite_palette(index, 0);
             self.draw_tile16(tile16, palette, &mut image, 0, 0, false);
 
-            let path = format!("rom_files/Graphics/tile16/sprite_{index}.png");
+            let path = dir.join(format!("sprite_{index}.png"));
             image.save_with_format(&path, ImageFormat::Png)?;
         }
         for (index, tile16) in self.tile_data.enemy_tile16_list.iter().enumerate() {
@@ -104,7 +105,7 @@ impl DrawData<'_> {
             let palette = get_enemy_palette(index);
             self.draw_tile16(tile16, palette, &mut image, 0, 0, false);
 
-            let path = format!("rom_files/Graphics/tile16/enemy_{index}.png");
+            let path = dir.join(format!("enemy_{index}.png"));
             image.save_with_format(&path, ImageFormat::Png)?;
         }
 
@@ -387,10 +388,8 @@ pub fn merge_maps(maps: Vec<(u8, RgbaImage)>) -> [(&'static str, RgbaImage); 2]
         let image = &mut images[index].1;
 
         for row in map.rows() {
-            let mut x = x_offset;
-            for pixel in row {
+            for (x, pixel) in (x_offset..).zip(row) {
                 image.put_pixel(x, y, *pixel);
-                x += 1;
             }
             y += 1;
         }
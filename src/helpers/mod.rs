@@ -1,11 +1,5 @@
-mod fs;
-mod option;
+pub mod fs;
 mod rand;
-mod result;
 
-pub use fs::{
-    create_dir_all, file_create, file_open, files_in_dir, read, read_dir, read_to_string, write,
-};
-pub use option::OptionExtension;
+pub use fs::{file_create, file_open, read, read_to_string, write};
 pub use rand::RemoveRandom;
-pub use result::ResultExtension;
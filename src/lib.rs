@@ -0,0 +1,23 @@
+#![recursion_limit = "256"]
+#![allow(clippy::cast_sign_loss)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::struct_field_names)]
+#![allow(clippy::struct_excessive_bools)]
+#![allow(clippy::match_same_arms)]
+#![allow(clippy::similar_names)]
+
+mod data;
+pub mod graphics;
+mod helpers;
+pub mod map;
+pub mod rando;
+pub mod rom;
+pub mod saves;
+
+/// File system functions which name the path in their errors
+pub use helpers::fs;
+
+use std::error::Error;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
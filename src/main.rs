@@ -1,4 +1,3 @@
-#![recursion_limit = "256"]
 #![allow(clippy::cast_sign_loss)]
 #![allow(clippy::cast_precision_loss)]
 #![allow(clippy::cast_possible_truncation)]
@@ -8,45 +7,67 @@
 #![allow(clippy::similar_names)]
 
 mod cli;
-mod data;
-mod graphics;
-mod helpers;
-mod map;
-mod rando;
-mod rom;
-mod saves;
 
-use std::error::Error;
+use std::process::ExitCode;
 
 use clap::Parser;
 use cli::{
-    draw_logic, export_all, export_rom, import_all, import_rom, import_saves, randomize, Action,
-    Cli,
+    diff_roms, disable_prompts, draw_logic, export_all, export_rom, export_saves, import_all,
+    import_rom, import_saves, rando_stats, randomize, recover_spoiler, report, use_rom,
+    validate_logic, Action, Cli, OptionExtension,
 };
-use helpers::OptionExtension;
-use saves::Saves;
+use heros_spirit_utils::Result;
+use serde_json::Value;
 
-pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
-
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    let action = cli.action.unwrap_or_prompt()?;
+    if cli.non_interactive {
+        disable_prompts();
+    }
+
+    report(run(cli.action), cli.json)
+}
+
+/// Returns what the action found, if it has anything to report
+fn run(action: Option<Action>) -> Result<Option<Value>> {
+    let action = action.unwrap_or_prompt()?;
 
     match action {
         Action::Randomize { args } => randomize(args)?,
-        Action::DrawLogic { args } => draw_logic(args.rom.unwrap_or_prompt()?),
-        Action::Export { args } => export_all(args.rom.unwrap_or_prompt()?),
-        Action::Import { args } => import_all(args.rom.unwrap_or_prompt()?),
-        Action::ExportSaves => {
-            Saves::decode();
+        Action::UseRom { args } => use_rom(&args.rom.unwrap_or_prompt()?)?,
+        Action::RandoStats { args } => return Ok(Some(rando_stats(args)?)),
+        Action::RecoverSpoiler { args } => recover_spoiler(args)?,
+        Action::DrawLogic { args } => draw_logic(args)?,
+        Action::ValidateLogic { args } => {
+            let diagnostics =
+                validate_logic(args.rom_args.rom.unwrap_or_prompt()?, args.mode, args.logic)?;
+            return Ok(Some(diagnostics));
+        }
+        Action::DiffRoms { args } => diff_roms(
+            args.first.unwrap_or_prompt()?,
+            args.second.unwrap_or_prompt()?,
+            &args.dir_args.dir,
+        )?,
+        Action::Export { args, save_args } => export_all(
+            args.rom_args.rom.unwrap_or_prompt()?,
+            &args.dir,
+            &save_args.dir(),
+        ),
+        Action::Import { args, save_args } => import_all(
+            args.rom_args.rom.unwrap_or_prompt()?,
+            &args.dir,
+            &save_args.dir(),
+        ),
+        Action::ExportSaves { args } => {
+            export_saves(&args.dir());
         }
-        Action::ImportSaves => import_saves(),
+        Action::ImportSaves { args } => import_saves(&args.dir()),
         Action::ExportRom { args } => {
-            export_rom(args.rom.unwrap_or_prompt()?);
+            export_rom(args.rom_args.rom.unwrap_or_prompt()?, &args.dir);
         }
-        Action::ImportRom { args } => import_rom(args.rom.unwrap_or_prompt()?),
+        Action::ImportRom { args } => import_rom(args.rom_args.rom.unwrap_or_prompt()?, &args.dir),
     }
 
-    Ok(())
+    Ok(None)
 }
@@ -0,0 +1,229 @@
+use std::{
+    cmp::max,
+    fmt::{self, Display},
+};
+
+use super::{Id, Map, Sprite, SpriteData};
+
+/// A way in which the maps of two roms differ, displayed without naming the map
+#[derive(Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// The map only exists in one of the roms
+    Missing {
+        map: u8,
+        in_first: bool,
+    },
+    /// The map has different dimensions, given as (width, height)
+    Size {
+        map: u8,
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+    Tile {
+        id: Id,
+        first: u8,
+        second: u8,
+    },
+    Sprite {
+        id: Id,
+        first: Option<SpriteData>,
+        second: Option<SpriteData>,
+    },
+    /// A transfer which leads somewhere else
+    Transfer {
+        id: Id,
+        first: Id,
+        second: Id,
+    },
+}
+
+impl Difference {
+    pub fn map(&self) -> u8 {
+        match self {
+            Self::Missing { map, .. } | Self::Size { map, .. } => *map,
+            Self::Tile { id, .. } | Self::Sprite { id, .. } | Self::Transfer { id, .. } => id.map,
+        }
+    }
+
+    /// The changed cell, if the difference concerns a single one
+    pub fn id(&self) -> Option<Id> {
+        match self {
+            Self::Missing { .. } | Self::Size { .. } => None,
+            Self::Tile { id, .. } | Self::Sprite { id, .. } | Self::Transfer { id, .. } => {
+                Some(*id)
+            }
+        }
+    }
+}
+
+/// Compares every map of `first` with the map of the same identifier in `second`
+pub fn diff_maps(first: &[Map], second: &[Map]) -> Vec<Difference> {
+    let mut differences = vec![];
+
+    for map in first {
+        match second
+            .iter()
+            .find(|other| other.identifier == map.identifier)
+        {
+            None => differences.push(Difference::Missing {
+                map: map.identifier,
+                in_first: true,
+            }),
+            Some(other) => diff_map(map, other, &mut differences),
+        }
+    }
+
+    for map in second {
+        if !first.iter().any(|other| other.identifier == map.identifier) {
+            differences.push(Difference::Missing {
+                map: map.identifier,
+                in_first: false,
+            });
+        }
+    }
+
+    differences
+}
+
+fn diff_map(first: &Map, second: &Map, differences: &mut Vec<Difference>) {
+    let size = |map: &Map| (map.tiles.first().map_or(0, Vec::len), map.tiles.len());
+    if size(first) != size(second) {
+        differences.push(Difference::Size {
+            map: first.identifier,
+            first: size(first),
+            second: size(second),
+        });
+    }
+
+    for (x, y, tile) in first.tiles_with_positions() {
+        if let Some(other) = second.tiles.get(y).and_then(|row| row.get(x)) {
+            if tile != *other {
+                differences.push(Difference::Tile {
+                    id: Id::new(first.identifier, x, y),
+                    first: tile,
+                    second: *other,
+                });
+            }
+        }
+    }
+
+    let height = max(first.sprites.len(), second.sprites.len());
+    for y in 0..height {
+        let width = max(
+            first.sprites.get(y).map_or(0, Vec::len),
+            second.sprites.get(y).map_or(0, Vec::len),
+        );
+
+        for x in 0..width {
+            let id = Id::new(first.identifier, x, y);
+
+            match (first.sprite(x, y), second.sprite(x, y)) {
+                (a, b) if a == b => {}
+                (Some(a), Some(b)) if a.is_transfer() && b.is_transfer() => {
+                    differences.push(Difference::Transfer {
+                        id,
+                        first: a.destination(),
+                        second: b.destination(),
+                    });
+                }
+                (a, b) => differences.push(Difference::Sprite {
+                    id,
+                    first: a.cloned(),
+                    second: b.cloned(),
+                }),
+            }
+        }
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { in_first, .. } => {
+                let rom = if *in_first { "first" } else { "second" };
+                write!(f, "only exists in the {rom} rom")
+            }
+            Self::Size { first, second, .. } => write!(
+                f,
+                "size {}x{} -> {}x{}",
+                first.0, first.1, second.0, second.1
+            ),
+            Self::Tile { id, first, second } => write!(f, "{id}: tile {first} -> {second}"),
+            Self::Sprite { id, first, second } => {
+                let name = |sprite: &Option<SpriteData>, other: &Option<SpriteData>| match sprite {
+                    None => "nothing".to_string(),
+                    Some(sprite) => {
+                        let name = Sprite::from(sprite.kind).name();
+                        match other {
+                            // Only the extra bytes differ, so show them
+                            Some(other) if other.kind == sprite.kind => {
+                                format!("{name} {:?}", sprite.extra_bytes)
+                            }
+                            _ => name,
+                        }
+                    }
+                };
+                write!(
+                    f,
+                    "{id}: {} -> {}",
+                    name(first, second),
+                    name(second, first)
+                )
+            }
+            Self::Transfer { id, first, second } => {
+                write!(f, "{id}: transfer to {first} -> {second}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_changed_ids() {
+        let sprite = |kind, extra_bytes: &[u8]| {
+            Some(SpriteData {
+                kind,
+                extra_bytes: extra_bytes.to_vec(),
+            })
+        };
+        let first = Map {
+            identifier: 42,
+            tiles: vec![vec![0, 1], vec![2, 3]],
+            sprites: vec![
+                vec![sprite(3, &[]), sprite(0, &[43, 1, 1, 2])],
+                vec![None, sprite(2, &[])],
+            ],
+        };
+        let mut second = first.clone();
+        second.tiles[1][0] = 5;
+        second.sprites[0][0] = sprite(72, &[]);
+        second.sprites[0][1] = sprite(0, &[43, 4, 4, 2]);
+        second.sprites[1][0] = sprite(2, &[]);
+        second.sprites[1][1] = None;
+        let other = Map {
+            identifier: 43,
+            tiles: vec![],
+            sprites: vec![],
+        };
+
+        let differences = diff_maps(&[first, other], &[second]);
+
+        assert_eq!(
+            differences
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "42.0.1: tile 2 -> 5",
+                "42.0.0: Sword -> WindRing",
+                "42.1.0: transfer to 43.1.1 -> 43.4.4",
+                "42.0.1: nothing -> GoldKey",
+                "42.1.1: GoldKey -> nothing",
+                "only exists in the first rom",
+            ]
+        );
+    }
+}
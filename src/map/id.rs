@@ -0,0 +1,89 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    str::{FromStr, SplitN},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// A cell of a map
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Id {
+    pub map: u8,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Id {
+    pub const fn new(map: u8, x: usize, y: usize) -> Self {
+        Self { map, x, y }
+    }
+}
+
+impl Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.map, self.x, self.y)
+    }
+}
+
+impl From<Id> for String {
+    fn from(value: Id) -> Self {
+        value.to_string()
+    }
+}
+
+impl Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Id {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self> {
+        fn parse_part<T>(parts: &mut SplitN<char>, s: &str) -> Result<T>
+        where
+            T: FromStr,
+            T::Err: Display,
+        {
+            let part = parts
+                .next()
+                .ok_or_else(|| "expected string with format <map>.<x>.<y>".to_string())?;
+            let part = part
+                .parse()
+                .map_err(|err| format!("invalid part \"{part}\" in \"{s}\": {err}"))?;
+            Ok(part)
+        }
+
+        let mut parts = s.splitn(4, '.');
+        let map = parse_part(&mut parts, s)?;
+        let x = parse_part(&mut parts, s)?;
+        let y = parse_part(&mut parts, s)?;
+
+        if let Some(remaining) = parts.next() {
+            Err(format!("trailing symbols \"{remaining}\" in \"{s}\""))?;
+        }
+
+        Ok(Id { map, x, y })
+    }
+}
+
+impl TryFrom<&str> for Id {
+    type Error = <Id as FromStr>::Err;
+
+    fn try_from(value: &str) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Id {
+    type Error = <Id as FromStr>::Err;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{rom::RomReader, Result};
 
@@ -23,35 +24,65 @@ impl MapColors {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Default, Deserialize)]
+/// Fields missing from a file are read as their default, and default fields are left out when writing
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase", default)]
 pub struct MapMeta {
+    #[serde(skip_serializing_if = "is_default")]
     pub is_bloodmoon_allowed: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub is_eclipse_allowed: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub is_mirror_allowed: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub is_night_allowed: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub is_chase_boss_map: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub is_snake_boss_map: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub is_witch_boss_map: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_glitch_enemies: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_glitch_music: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_ngp_music: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_ngpp_music: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_possum_music: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_glitch_colors: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_ngp_colors: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub has_ngpp_colors: bool,
+    #[serde(skip_serializing_if = "is_default")]
     pub music: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub music_alt: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub music_night: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub music_night_alt: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub colors: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub colors_alt: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub colors_night: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub colors_night_alt: usize,
+    #[serde(skip_serializing_if = "is_default")]
     pub possum_x: i32,
+    #[serde(skip_serializing_if = "is_default")]
     pub possum_y: i32,
+    #[serde(skip_serializing_if = "is_default")]
     pub night_swords: u8,
+    /// Fields not listed above, kept so writing the file back doesn't lose them
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
 }
 
 impl MapMeta {
@@ -61,14 +92,7 @@ impl MapMeta {
             .iter()
             .map(|index: &usize| {
                 let file = rom.archive.by_index(*index)?;
-                let name = file.name();
-                let id = name
-                    .strip_prefix("Maps/Metadata/map")
-                    .and_then(|name| name.strip_suffix(".json"))
-                    .ok_or_else(|| format!("invalid map meta file \"{name}\""))?;
-                let id = id.parse().map_err(|err| {
-                    format!("invalid map meta identifier \"{id}\" in \"{name}\": {err}")
-                })?;
+                let id = Self::identifier(file.name())?;
 
                 let map_meta = serde_json::from_reader(file)?;
 
@@ -76,4 +100,21 @@ impl MapMeta {
             })
             .collect()
     }
+
+    /// Map identifier from the name of a file in "Maps/Metadata/"
+    pub fn identifier(name: &str) -> Result<usize> {
+        let id = name
+            .strip_prefix("Maps/Metadata/map")
+            .and_then(|name| name.strip_suffix(".json"))
+            .ok_or_else(|| format!("invalid map meta file \"{name}\""))?;
+        let id = id
+            .parse()
+            .map_err(|err| format!("invalid map meta identifier \"{id}\" in \"{name}\": {err}"))?;
+
+        Ok(id)
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
 }
@@ -1,15 +1,19 @@
+mod diff;
+mod id;
 mod meta;
 mod sprite;
 mod stats;
 mod tiled;
 
+pub use diff::{diff_maps, Difference};
+pub use id::Id;
 pub use meta::{MapColors, MapMeta};
 pub use sprite::{Collectible, Door, Enemy, Gear, Sprite, SpriteData, Things};
 
 use crate::rom::{self, RomReader};
 use crate::Result;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Map {
     pub identifier: u8,
     pub tiles: Vec<Vec<u8>>,
@@ -60,6 +64,46 @@ impl Map {
     pub const HHM_THE_UNDERWORLD: u8 = 45;
     pub const HHM_THRONE_ROOM: u8 = 46;
 
+    pub const MAIN_CAMPAIGN: [u8; 31] = [
+        Self::DUST_SHELF,
+        Self::NORTH_MUNDEMAN,
+        Self::SOUTH_MUNDEMAN,
+        Self::VERDANT_COAST,
+        Self::OTHERWORLD_ARENA,
+        Self::CASTLE_GROUNDS,
+        Self::SANCTUARY,
+        Self::THE_TUNNELS,
+        Self::GLITCH,
+        Self::LUDDERSHORE,
+        Self::THE_TUNDRA,
+        Self::FROZEN_SHORE,
+        Self::HALLOW_GROUND,
+        Self::SOUTHERN_SWAMP,
+        Self::DRAGONS_LAIR,
+        Self::CORRUPTED_CASTLE,
+        Self::CASTLE_MONILLUD,
+        Self::THE_UNDERWORLD,
+        Self::OTHERWORLD,
+        Self::MOLTEN_CAVERN,
+        Self::THE_DUNGEONS,
+        Self::ITEM_SHOP,
+        Self::CONVERGENCE,
+        Self::TRIAL_OF_REALITY,
+        Self::FALLEN_WORLD,
+        Self::ROAD_TO_HELL,
+        Self::HAUNTED_MANSE,
+        Self::MOONWELL,
+        Self::BETWEEN_WORLDS,
+        Self::SMUGGLERS_ROAD,
+        Self::SMUGGLERS_RUIN,
+    ];
+    pub const HEROIC_HARDCORE: [u8; 4] = [
+        Self::HHM_CASTLE_GROUNDS,
+        Self::HHM_CASTLE_MONILLUD,
+        Self::HHM_STRANGE_AREA,
+        Self::HHM_THE_UNDERWORLD,
+    ];
+
     pub fn parse_all(rom: &mut RomReader) -> Result<Vec<Self>> {
         rom.index
             .maps
@@ -73,7 +117,11 @@ impl Map {
     }
 
     pub fn is_hardcore(&self) -> bool {
-        (Map::HHM_CASTLE_GROUNDS..=Map::HHM_THE_UNDERWORLD).contains(&self.identifier)
+        Self::HEROIC_HARDCORE.contains(&self.identifier)
+    }
+
+    pub fn is_main_campaign(&self) -> bool {
+        Self::MAIN_CAMPAIGN.contains(&self.identifier)
     }
 
     pub fn tiles(&self) -> impl Iterator<Item = u8> + use<'_> {
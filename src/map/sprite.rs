@@ -2,12 +2,14 @@ use std::cmp::Ordering;
 
 use std::ops::RangeInclusive;
 
-use serde::Serialize;
-use strum::VariantNames;
+use serde::{Deserialize, Serialize};
+use strum::{EnumString, VariantNames};
 
 use crate::Result;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, VariantNames)]
+use super::{Id, Map};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, VariantNames, EnumString)]
 pub enum Collectible {
     GoldKey,
     SilverKey,
@@ -30,7 +32,9 @@ pub enum Collectible {
     PossumCoin = 34,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, VariantNames)]
+#[derive(
+    Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, VariantNames, EnumString,
+)]
 pub enum Gear {
     Hammer = 11,
     Boots = 12,
@@ -213,6 +217,37 @@ const FIVE_BYTE_IDS: [u8; 6] = [0, 14, 147, 190, 193, 197];
 const FIVE_BYTE_ID_RANGES: [RangeInclusive<u8>; 1] = [26..=41];
 
 impl SpriteData {
+    pub fn is_transfer(&self) -> bool {
+        matches!(self.kind.into(), Sprite::Things(Things::Transfer))
+    }
+
+    /// Where a transfer leads to
+    pub fn destination(&self) -> Id {
+        let x = self.extra_bytes[1] as usize;
+        let y = self.extra_bytes[2] as usize;
+        Id::new(self.extra_bytes[0], x, y)
+    }
+
+    /// The transfer adjacent to where a transfer leads to
+    pub fn destination_transfer(&self, maps: &[Map]) -> Option<Id> {
+        fn transfer_at(map: &Map, x: usize, y: usize) -> Option<Id> {
+            map.sprite(x, y).and_then(|sprite| {
+                sprite
+                    .is_transfer()
+                    .then_some(Id::new(map.identifier, x, y))
+            })
+        }
+
+        let Id { map, x, y } = self.destination();
+        let map = maps.iter().find(|m| m.identifier == map)?;
+
+        x.checked_sub(1)
+            .and_then(|x| transfer_at(map, x, y))
+            .or_else(|| x.checked_add(1).and_then(|x| transfer_at(map, x, y)))
+            .or_else(|| y.checked_sub(1).and_then(|y| transfer_at(map, x, y)))
+            .or_else(|| y.checked_add(1).and_then(|y| transfer_at(map, x, y)))
+    }
+
     fn read_step(sprite_data: &[u8], index: &mut usize) -> u8 {
         let read = sprite_data[*index];
         *index += 1;
@@ -275,6 +310,16 @@ impl Sprite {
             _ => (1, 1),
         }
     }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Collectible(collectible) => format!("{collectible:?}"),
+            Self::Gear(gear) => format!("{gear:?}"),
+            Self::Door(door) => format!("{door:?}Door"),
+            Self::Enemy(enemy) => format!("{enemy:?}"),
+            other => format!("{other:?}"),
+        }
+    }
 }
 
 impl From<u8> for Sprite {
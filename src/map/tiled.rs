@@ -229,6 +229,7 @@ impl Map {
         offset: u16,
     ) -> Result<Vec<Vec<Option<SpriteData>>>> {
         let mut sprites = vec![vec![None; width]; height];
+        let mut invalid_sprite = None;
 
         let sprite_data = tmx.match_indices("<objectgroup").find_map(|(tags_start, _)| {
             let tags_end = tmx[tags_start..].find('>')? + tags_start;
@@ -244,7 +245,7 @@ impl Map {
                     let tags = tags.split(' ').collect::<Vec<_>>();
                     let gid = tags.iter().find_map(|tag| tag.strip_prefix("gid=\""))?.strip_suffix('"')?.parse::<u16>().ok()?;
                     if gid < offset {
-                        eprintln!("Map contains invalid sprite with gid {gid}. Did you place a tile on the sprite layer by accident?");
+                        invalid_sprite = Some(format!("Map contains invalid sprite with gid {gid}. Did you place a tile on the sprite layer by accident?"));
                         return None;
                     }
                     let kind = (gid - offset) as u8;
@@ -276,6 +277,10 @@ impl Map {
             Some(sprites)
         }).ok_or("Failed to read Sprites layer")?;
 
+        if let Some(err) = invalid_sprite {
+            Err(err)?;
+        }
+
         for (x, y, kind, extra_bytes) in sprite_data {
             sprites[y][x] = Some(SpriteData { kind, extra_bytes });
         }
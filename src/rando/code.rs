@@ -0,0 +1,143 @@
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use serde_json::{Map, Value};
+
+use crate::Result;
+
+use super::{Logic, Settings};
+
+/// Codes are only accepted by the generator version they were created with.
+///
+/// Bump this whenever the same settings and seed may produce a different rom, such as after changing how
+/// anything is shuffled or changing a default setting.
+pub const GENERATOR_VERSION: u32 = 2;
+
+/// Settings and seed read back from a share code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedSeed {
+    pub settings: Settings,
+    pub seed: String,
+    /// Hash of the logic file the code was created with
+    pub logic_hash: u64,
+}
+
+impl SharedSeed {
+    /// Fails unless `logic` is the same logic file the code was created with
+    pub fn check_logic(&self, logic: &Logic) -> Result<()> {
+        if self.logic_hash != logic.hash() {
+            Err("share code was created with a different logic file")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Bundle settings and seed into a string which can be shared to reproduce the same rom
+///
+/// Only settings which differ from the defaults are included to keep the code short.
+pub fn share_code(settings: &Settings, logic: &Logic, seed: &str) -> Result<String> {
+    let defaults = settings_map(&Settings::default())?;
+    let changed = settings_map(settings)?
+        .into_iter()
+        .filter(|(key, value)| defaults.get(key) != Some(value))
+        .collect::<Map<_, _>>();
+
+    let json = serde_json::to_vec(&(
+        GENERATOR_VERSION,
+        format!("{:016x}", logic.hash()),
+        changed,
+        seed,
+    ))?;
+
+    Ok(BASE64_URL_SAFE_NO_PAD.encode(json))
+}
+
+fn settings_map(settings: &Settings) -> Result<Map<String, Value>> {
+    Ok(serde_json::from_value(serde_json::to_value(settings)?)?)
+}
+
+/// Read back the settings and seed from a share code
+pub fn parse_share_code(code: &str) -> Result<SharedSeed> {
+    let json = BASE64_URL_SAFE_NO_PAD
+        .decode(code.trim())
+        .map_err(|err| format!("invalid share code: {err}"))?;
+
+    let value = serde_json::from_slice::<Value>(&json)
+        .map_err(|err| format!("invalid share code: {err}"))?;
+    let version = value.get(0).and_then(Value::as_u64);
+    if version != Some(GENERATOR_VERSION.into()) {
+        Err(match version {
+            Some(version) => format!(
+                "share code was created with generator version {version}, but this is version {GENERATOR_VERSION}"
+            ),
+            None => "share code was created with an older generator version".to_string(),
+        })?;
+    }
+
+    let (_, logic_hash, settings, seed) =
+        serde_json::from_value::<(u32, String, Settings, String)>(value)
+            .map_err(|err| format!("invalid share code: {err}"))?;
+    let logic_hash =
+        u64::from_str_radix(&logic_hash, 16).map_err(|err| format!("invalid share code: {err}"))?;
+
+    Ok(SharedSeed {
+        settings,
+        seed,
+        logic_hash,
+    })
+}
+
+/// FNV-1a over the compact json of the logic file, so formatting and line endings don't matter
+pub(super) fn logic_hash(logic: &Value) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    logic.to_string().bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_logic() -> Logic {
+        serde_json::from_str(r#"{"areas":{"Spawn":{}}}"#).unwrap()
+    }
+
+    #[test]
+    fn roundtrip() {
+        let settings = Settings {
+            doors: false,
+            ..Settings::default()
+        };
+
+        let code = share_code(&settings, &test_logic(), "seed").unwrap();
+        let shared = parse_share_code(&code).unwrap();
+
+        assert_eq!(shared.settings, settings);
+        assert_eq!(shared.seed, "seed");
+        assert!(shared.check_logic(&test_logic()).is_ok());
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let old = r#"{"version":"0.1.0","settings":{},"seed":"seed"}"#;
+        let newer = format!(r#"[{},"0",{{}},"seed"]"#, GENERATOR_VERSION + 1);
+
+        for json in [old, &newer] {
+            let code = BASE64_URL_SAFE_NO_PAD.encode(json);
+            assert!(parse_share_code(&code).is_err());
+        }
+    }
+
+    #[test]
+    fn logic_mismatch() {
+        let code = share_code(&Settings::default(), &test_logic(), "seed").unwrap();
+        let shared = parse_share_code(&code).unwrap();
+
+        let mut other = test_logic();
+        other.hash = logic_hash(&serde_json::json!({"areas": {"Other": {}}}));
+
+        assert!(shared.check_logic(&other).is_err());
+    }
+}
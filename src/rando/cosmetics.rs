@@ -0,0 +1,175 @@
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+};
+
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64Mcg;
+use rand_seeder::Seeder;
+
+use crate::{
+    map::{Map, MapMeta},
+    Result,
+};
+
+use super::Cosmetics;
+
+/// Shuffles palettes and music between `maps`. Maps which shared a palette or track before still share one afterwards.
+///
+/// This uses its own rng, so the same seed results in the same items regardless of the cosmetics chosen.
+/// Fails without changing anything if a map uses a palette index of `palettes` or above.
+pub fn shuffle_cosmetics(
+    map_meta: &mut HashMap<usize, MapMeta>,
+    palettes: usize,
+    maps: &[Map],
+    cosmetics: Cosmetics,
+    seed: &str,
+) -> Result<()> {
+    let mut rng: Pcg64Mcg = Seeder::from(format!("{seed} cosmetics")).into_rng();
+    let identifiers = maps
+        .iter()
+        .filter(|map| map.identifier != Map::GLITCH)
+        .map(|map| usize::from(map.identifier))
+        .collect::<HashSet<_>>();
+    let mut metas = map_meta
+        .iter_mut()
+        .filter(|(id, _)| identifiers.contains(id))
+        .sorted_unstable_by_key(|(id, _)| **id)
+        .collect::<Vec<_>>();
+
+    if cosmetics.palettes {
+        for (id, meta) in &mut metas {
+            if let Some(colors) = colors_mut(meta).find(|colors| **colors >= palettes) {
+                Err(format!(
+                    "map {id} uses palette {colors}, but there are only {palettes} palettes"
+                ))?;
+            }
+        }
+
+        let mut shuffled = (0..palettes).collect::<Vec<_>>();
+        shuffled.shuffle(&mut rng);
+
+        for (_, meta) in &mut metas {
+            for colors in colors_mut(meta) {
+                *colors = shuffled[*colors];
+            }
+        }
+    }
+
+    if cosmetics.music {
+        let tracks = metas
+            .iter_mut()
+            .flat_map(|(_, meta)| music_mut(meta).map(|music| *music).collect::<Vec<_>>())
+            .sorted_unstable()
+            .dedup()
+            .collect::<Vec<_>>();
+        let mut shuffled = tracks.clone();
+        shuffled.shuffle(&mut rng);
+        let shuffled = tracks.into_iter().zip(shuffled).collect::<HashMap<_, _>>();
+
+        for (_, meta) in &mut metas {
+            for music in music_mut(meta) {
+                *music = shuffled[music];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// A variant of 0 means the map doesn't have that variant
+fn colors_mut(meta: &mut MapMeta) -> impl Iterator<Item = &mut usize> {
+    let variants = [
+        &mut meta.colors_alt,
+        &mut meta.colors_night,
+        &mut meta.colors_night_alt,
+    ];
+
+    iter::once(&mut meta.colors).chain(variants.into_iter().filter(|colors| **colors != 0))
+}
+
+// Track 0 is silence, which is left in place
+fn music_mut(meta: &mut MapMeta) -> impl Iterator<Item = &mut usize> {
+    [
+        &mut meta.music,
+        &mut meta.music_alt,
+        &mut meta.music_night,
+        &mut meta.music_night_alt,
+    ]
+    .into_iter()
+    .filter(|music| **music != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_cosmetics_stay_shared() {
+        let maps = (1..=8)
+            .map(|identifier| Map {
+                identifier,
+                tiles: vec![],
+                sprites: vec![],
+            })
+            .collect::<Vec<_>>();
+        let vanilla = maps
+            .iter()
+            .map(|map| {
+                let id = usize::from(map.identifier);
+                let meta = MapMeta {
+                    colors: id % 3,
+                    colors_night: 4,
+                    music: id % 2 + 10,
+                    ..MapMeta::default()
+                };
+                (id, meta)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let cosmetics = Cosmetics {
+            palettes: true,
+            music: true,
+        };
+        let mut shuffled = vanilla.clone();
+        shuffle_cosmetics(&mut shuffled, 5, &maps, cosmetics, "seed").unwrap();
+
+        let mut again = vanilla.clone();
+        shuffle_cosmetics(&mut again, 5, &maps, cosmetics, "seed").unwrap();
+        assert_eq!(shuffled, again);
+
+        let mut too_few = vanilla.clone();
+        assert!(shuffle_cosmetics(&mut too_few, 4, &maps, cosmetics, "seed").is_err());
+        assert_eq!(too_few, vanilla);
+
+        for (a, b) in vanilla.keys().tuple_combinations() {
+            assert_eq!(
+                vanilla[a].colors == vanilla[b].colors,
+                shuffled[a].colors == shuffled[b].colors
+            );
+            assert_eq!(
+                vanilla[a].music == vanilla[b].music,
+                shuffled[a].music == shuffled[b].music
+            );
+        }
+        for meta in shuffled.values() {
+            assert!(meta.colors < 5);
+            assert!([10, 11].contains(&meta.music));
+            assert_eq!(meta.colors_alt, 0);
+            assert_eq!(meta.music_alt, 0);
+        }
+    }
+
+    #[test]
+    fn unknown_meta_fields_survive() {
+        let meta =
+            serde_json::from_str::<MapMeta>(r#"{"Music": 3, "SomethingNew": [1, 2]}"#).unwrap();
+        let json = serde_json::to_value(&meta).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({"Music": 3, "SomethingNew": [1, 2]})
+        );
+    }
+}
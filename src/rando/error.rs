@@ -0,0 +1,68 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+use crate::map::Sprite;
+
+use super::id::Id;
+
+/// Problems with the logic or maps which make randomizing impossible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RandoError {
+    UnknownMap(Id),
+    MissingSprite(Id),
+    UnexpectedSprite {
+        id: Id,
+        sprite: Sprite,
+        expected: &'static str,
+    },
+    UnknownArea(String),
+    UnknownRequirement(Id),
+    UnknownMacro(String),
+    UnknownTrick(String),
+    /// A macro or trick that was looked up without the logic defining it
+    UnresolvedRequirement(String),
+    MissingTargetTransfer {
+        transfer: Id,
+        destination: Id,
+    },
+    /// Decoupled transfers need two groups to link together
+    TooFewTransferGroups,
+}
+
+impl Display for RandoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownMap(id) => write!(f, "unknown map identifier {} in {id}", id.map),
+            Self::MissingSprite(id) => write!(f, "failed to match {id} to a sprite"),
+            Self::UnexpectedSprite {
+                id,
+                sprite,
+                expected,
+            } => write!(f, "unexpected {sprite:?} as {expected} {id}"),
+            Self::UnknownArea(area) => write!(f, "path target \"{area}\" doesn't exist"),
+            Self::UnknownRequirement(id) => {
+                write!(f, "unknown requirement {id}, it is no item, door or enemy")
+            }
+            Self::UnknownMacro(name) => write!(f, "unknown macro \"@{name}\""),
+            Self::UnknownTrick(name) => write!(f, "unknown trick \"Trick.{name}\""),
+            Self::UnresolvedRequirement(name) => {
+                write!(f, "\"{name}\" can only be resolved through the logic")
+            }
+            Self::MissingTargetTransfer {
+                transfer,
+                destination,
+            } => write!(
+                f,
+                "failed to find target transfer for {transfer} (targets {destination})"
+            ),
+            Self::TooFewTransferGroups => write!(
+                f,
+                "decoupled transfers need at least two groups of connected areas with transfers"
+            ),
+        }
+    }
+}
+
+impl Error for RandoError {}
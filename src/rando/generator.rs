@@ -1,24 +1,29 @@
 // TODO investigate seed: LeatherDigressiveWitch
 
-use indexmap::IndexSet;
+use std::{collections::HashMap, result::Result as StdResult};
+
+use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
 use rand::{
-    seq::{IteratorRandom, SliceRandom},
-    SeedableRng,
+    seq::{IndexedRandom, IteratorRandom, SliceRandom},
+    Rng, SeedableRng,
 };
 use rand_pcg::Pcg64Mcg;
 
 use crate::{
     helpers::RemoveRandom,
-    map::{Collectible, Door, Enemy, Map, Sprite},
+    map::{Collectible, Door, Enemy, Gear, Map, Sprite},
     Result,
 };
 
 use super::{
-    id::Id,
+    id::{FindId, Id},
     logic::{Logic, Reach, RequirementMap},
     pool::Pool,
     seed::Seed,
     spoiler::ItemSpoiler,
+    transfers::{start_positions, vanilla_transfers},
+    Balancing, EnemyMode, Plando, RandoError, Settings, SphereBias, TransferMode,
 };
 
 pub struct Generator<'logic> {
@@ -27,27 +32,72 @@ pub struct Generator<'logic> {
     reach_cache: IndexSet<Id>,
     pool: Pool,
     needs_placement: IndexSet<Id>,
+    /// Locations reachable with every item, all of them have to be collectible in the finished seed
+    reachable: IndexSet<Id>,
     seed: Seed,
-    spoiler: ItemSpoiler,
+    gear: IndexMap<Gear, Id>,
+    balancing: Balancing,
+    /// Everything but gear, which balancing assumes to be owned
+    filler: Pool,
 }
 
 impl<'logic> Generator<'logic> {
-    pub fn new(maps: &[Map], logic: &'logic Logic, rng: &mut Pcg64Mcg) -> Result<Self> {
+    pub fn new(
+        maps: &[Map],
+        logic: &'logic Logic,
+        settings: &Settings,
+        rng: &mut Pcg64Mcg,
+    ) -> Result<Self> {
         let mut rng = Pcg64Mcg::from_rng(rng);
 
         let mut seed = Seed::default();
         let mut requirement_map = RequirementMap::new(logic);
 
-        let logic_transfers = seed.generate_transfers(maps, logic, &mut rng);
-        seed.blind_shuffle::<Door>(maps, &mut requirement_map, &mut rng);
-        seed.blind_shuffle::<Enemy>(maps, &mut requirement_map, &mut rng);
+        let logic_transfers = match (settings.transfers, settings.transfer_mode) {
+            (false, _) => vanilla_transfers(maps, logic)?,
+            (true, TransferMode::Coupled) => seed.generate_transfers(maps, logic, &mut rng)?,
+            (true, TransferMode::Decoupled) => {
+                seed.generate_decoupled_transfers(maps, logic, &mut rng)?
+            }
+        };
 
-        let reach = Reach::new(logic, logic_transfers, requirement_map);
-        let pool = Pool::new(logic, maps);
-        let reach_cache = reach.reach(&pool);
-        let needs_placement = reach_cache.clone();
+        if settings.doors {
+            seed.blind_shuffle::<Door>(maps, logic, &mut requirement_map, &mut rng)?;
+        } else {
+            Door::keep_vanilla(maps, &mut requirement_map)?;
+        }
+
+        match (settings.enemies, settings.enemy_mode) {
+            (false, _) => Enemy::keep_vanilla(maps, &mut requirement_map)?,
+            (true, EnemyMode::Blind) => {
+                seed.blind_shuffle::<Enemy>(maps, logic, &mut requirement_map, &mut rng)?;
+            }
+            // Enemies are placed once all items are, until then they don't block anything
+            (true, EnemyMode::Progressive) => {
+                for id in Enemy::iter(maps) {
+                    requirement_map.insert(id, vec![vec![]]);
+                }
+            }
+        }
 
-        let spoiler = ItemSpoiler::default();
+        let start = if settings.random_start {
+            let (area, position) = start_positions(maps, logic)?
+                .into_iter()
+                .choose(&mut rng)
+                .ok_or("no area to start in")?;
+            seed.start = Some(position);
+            area
+        } else {
+            "Spawn"
+        };
+
+        let reach = Reach::new(logic, start, logic_transfers, requirement_map, settings)?;
+        let pool = Pool::new(logic, maps, &settings.plando)?;
+        let reach_cache = reach.reach(&pool)?;
+        let needs_placement = reach_cache.clone();
+        let reachable = reach_cache.clone();
+        let mut filler = pool.clone();
+        filler.retain(|sprite| !matches!(sprite, Sprite::Gear(_)));
 
         let mut generator = Generator {
             rng,
@@ -55,13 +105,24 @@ impl<'logic> Generator<'logic> {
             reach_cache,
             pool,
             needs_placement,
+            reachable,
             seed,
-            spoiler,
+            gear: IndexMap::new(),
+            balancing: settings.balancing.clone(),
+            filler,
         };
 
+        generator.lock_placements(logic, &settings.plando)?;
+
+        if !settings.items {
+            generator.keep_vanilla_items(maps, logic, &settings.plando)?;
+            return Ok(generator);
+        }
+
         for item in logic.items() {
-            if !generator.needs_placement.contains(&item) {
-                eprintln!("item {item} is unreachable");
+            if !generator.needs_placement.contains(&item)
+                && !settings.plando.placements.contains_key(&item)
+            {
                 generator.place_unreachable(item)?;
             }
         }
@@ -69,6 +130,54 @@ impl<'logic> Generator<'logic> {
         Ok(generator)
     }
 
+    fn lock_placements(&mut self, logic: &Logic, plando: &Plando) -> Result<()> {
+        for (&id, item) in &plando.placements {
+            if !logic.items().contains(&id) {
+                Err(format!("plando placement {id} is not an item location"))?;
+            }
+
+            if !self.reach_cache.contains(&id) {
+                Err(format!("plando places {item} at unreachable {id}"))?;
+            }
+
+            if !matches!(item.0, Sprite::Gear(_)) {
+                self.filler.push(item.0);
+            }
+
+            self.commit_placement(id, item.0);
+        }
+
+        Ok(())
+    }
+
+    fn keep_vanilla_items(&mut self, maps: &[Map], logic: &Logic, plando: &Plando) -> Result<()> {
+        for item in logic.items() {
+            if plando.placements.contains_key(&item) {
+                continue;
+            }
+
+            let sprite = item.find_sprite(maps)?.kind.into();
+
+            if let Sprite::Gear(gear) = sprite {
+                let mut pool = self.pool.clone();
+                pool.retain(|other| *other != sprite);
+
+                if !self.reach.reach(&pool)?.contains(&item) {
+                    Err(format!("{gear:?} at {item} is unreachable without itself"))?;
+                }
+
+                self.gear.insert(gear, item);
+            } else if !self.reach_cache.contains(&item) {
+                Err(format!("item {item} is unreachable"))?;
+            }
+        }
+
+        self.pool.clear();
+        self.needs_placement.clear();
+
+        Ok(())
+    }
+
     fn place_unreachable(&mut self, location: Id) -> Result<()> {
         let sprite = self.pool.choose_remove_filler(&mut self.rng)?;
         self.seed.push((location, sprite.into()));
@@ -83,11 +192,21 @@ impl<'logic> Generator<'logic> {
     pub fn place_item(&mut self) -> Result<()> {
         let sprite = self.random_item()?;
 
-        let location = *self
-            .needs_placement
-            .intersection(&self.reach_cache)
-            .choose(&mut self.rng)
-            .unwrap();
+        let location = match sprite {
+            Sprite::Gear(gear) if self.balancing != Balancing::default() => {
+                let candidates = self
+                    .needs_placement
+                    .intersection(&self.reach_cache)
+                    .copied()
+                    .collect();
+                self.balanced_location(gear, candidates)?
+            }
+            _ => *self
+                .needs_placement
+                .intersection(&self.reach_cache)
+                .choose(&mut self.rng)
+                .unwrap(),
+        };
         self.needs_placement.swap_remove(&location);
 
         self.commit_placement(location, sprite);
@@ -95,15 +214,257 @@ impl<'logic> Generator<'logic> {
         Ok(())
     }
 
-    pub fn finish(self) -> (Seed, ItemSpoiler) {
-        (self.seed, self.spoiler)
+    pub fn assumed_fill(&mut self) -> Result<()> {
+        let (mut gear, mut filler) = self
+            .pool
+            .iter()
+            .partition::<Vec<_>, _>(|sprite| matches!(sprite, Sprite::Gear(_)));
+        gear.shuffle(&mut self.rng);
+        filler.shuffle(&mut self.rng);
+
+        let mut placements = HashMap::new();
+
+        for sprite in gear {
+            let index = self.pool.iter().position(|item| *item == sprite).unwrap();
+            self.pool.swap_remove(index);
+
+            let reach = self.reach.reach_with_placements(&self.pool, &placements)?;
+            let location = match sprite {
+                Sprite::Gear(gear) if self.balancing != Balancing::default() => {
+                    let candidates = self.needs_placement.intersection(&reach).copied().collect();
+                    self.balanced_location(gear, candidates)?
+                }
+                _ => *self
+                    .needs_placement
+                    .intersection(&reach)
+                    .choose(&mut self.rng)
+                    .ok_or_else(|| format!("failed to safely place {sprite:?}"))?,
+            };
+
+            placements.insert(location, sprite);
+            self.commit_placement(location, sprite);
+        }
+
+        for location in self.needs_placement.clone() {
+            let sprite = filler.pop().ok_or("ran out of filler items")?;
+            self.commit_placement(location, sprite);
+        }
+
+        self.pool.clear();
+
+        Ok(())
+    }
+
+    fn balanced_location(&mut self, gear: Gear, candidates: Vec<Id>) -> Result<Id> {
+        let spheres = self.gear_spheres()?;
+        let min_sphere = self.balancing.min_sphere.get(&gear).copied().unwrap_or(0);
+
+        // Locations behind gear that has not been placed yet come after all known spheres
+        let mut by_sphere = vec![vec![]; spheres.len() + 1];
+        for id in candidates {
+            let sphere = spheres
+                .iter()
+                .position(|sphere| sphere.contains(&id))
+                .unwrap_or(spheres.len());
+            if sphere >= min_sphere {
+                by_sphere[sphere].push(id);
+            }
+        }
+        by_sphere.retain(|locations| !locations.is_empty());
+
+        let count = by_sphere.len();
+        if count == 0 {
+            Err(format!(
+                "failed to place {gear:?} in sphere {min_sphere} or later"
+            ))?;
+        }
+
+        let sphere = match self.balancing.bias {
+            SphereBias::Off => {
+                let locations = by_sphere.concat();
+                return Ok(*locations.choose(&mut self.rng).unwrap());
+            }
+            SphereBias::Even => self.rng.random_range(..count),
+            SphereBias::Early => *(0..count)
+                .collect::<Vec<_>>()
+                .choose_weighted(&mut self.rng, |index| count - index)?,
+            SphereBias::Late => *(0..count)
+                .collect::<Vec<_>>()
+                .choose_weighted(&mut self.rng, |index| index + 1)?,
+        };
+
+        Ok(*by_sphere[sphere].choose(&mut self.rng).unwrap())
+    }
+
+    fn gear_spheres(&self) -> StdResult<Vec<IndexSet<Id>>, RandoError> {
+        let placements = self
+            .gear
+            .iter()
+            .map(|(gear, id)| (*id, Sprite::Gear(*gear)))
+            .collect();
+        self.reach.location_spheres(&placements, &self.filler)
+    }
+
+    /// Placing later gear may open up shortcuts, so the minimum spheres are checked again once everything is placed
+    pub fn check_min_spheres(&self) -> Result<()> {
+        if self.balancing.min_sphere.is_empty() {
+            return Ok(());
+        }
+
+        let spheres = self.gear_spheres()?;
+        for (gear, min_sphere) in &self.balancing.min_sphere {
+            let Some(id) = self.gear.get(gear) else {
+                continue;
+            };
+            let sphere = spheres
+                .iter()
+                .position(|sphere| sphere.contains(id))
+                .unwrap_or(spheres.len());
+            if sphere < *min_sphere {
+                Err(format!(
+                    "{gear:?} ended up in sphere {sphere}, earlier than {min_sphere}"
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn progressive_enemy_shuffle(&mut self, maps: &[Map], logic: &Logic) -> Result<()> {
+        let items = self.placed_items(maps, logic)?;
+        let references = logic.requirement_references();
+
+        let mut unassigned = IndexSet::new();
+        let mut remaining = IndexMap::<_, Vec<_>>::new();
+
+        for id in Enemy::iter(maps) {
+            let enemy = Enemy::map(id, maps)?;
+            let requirement_map = self.reach.requirement_map_mut();
+
+            if logic.is_excluded(id) {
+                requirement_map.insert(id, enemy.requirement());
+            } else {
+                requirement_map.insert(id, vec![]);
+                remaining
+                    .entry(enemy.sprite().tile_size())
+                    .or_default()
+                    .push(enemy);
+                unassigned.insert(id);
+            }
+        }
+
+        loop {
+            let (areas, inventory) = self.reach.explore(&items)?;
+
+            let frontier = unassigned
+                .iter()
+                .filter(|id| {
+                    references
+                        .get(id)
+                        .is_some_and(|names| names.iter().any(|name| areas.contains(name.as_str())))
+                })
+                .copied()
+                .collect::<Vec<_>>();
+
+            if frontier.is_empty() {
+                break;
+            }
+
+            // Enemies which can't be beaten with what is reachable so far wait for a later round
+            let mut progress = false;
+            for &id in &frontier {
+                let footprint = Enemy::map(id, maps)?.sprite().tile_size();
+                let candidates = &mut remaining[&footprint];
+
+                let index = (0..candidates.len())
+                    .filter(|index| {
+                        candidates[*index]
+                            .requirement()
+                            .iter()
+                            .any(|items| inventory.contains_all(items))
+                    })
+                    .choose(&mut self.rng);
+
+                if let Some(index) = index {
+                    let enemy = candidates.swap_remove(index);
+                    self.commit_enemy(id, enemy);
+                    unassigned.swap_remove(&id);
+                    progress = true;
+                }
+            }
+
+            if !progress {
+                if self.uncollected(&items)?.is_empty() {
+                    break;
+                }
+                Err(format!(
+                    "no enemy that can be beaten is left for {}",
+                    frontier.iter().format(", ")
+                ))?;
+            }
+        }
+
+        for id in unassigned {
+            let footprint = Enemy::map(id, maps)?.sprite().tile_size();
+            let enemy = remaining[&footprint].choose_remove(&mut self.rng);
+            self.commit_enemy(id, enemy);
+        }
+
+        Ok(())
+    }
+
+    /// Reachable locations which can't be collected with the items placed at `items`
+    fn uncollected(&self, items: &HashMap<Id, Sprite>) -> Result<Vec<Id>> {
+        let collected = self
+            .reach
+            .spheres(items)?
+            .into_iter()
+            .flatten()
+            .collect::<IndexSet<_>>();
+
+        Ok(self.reachable.difference(&collected).copied().collect())
+    }
+
+    fn commit_enemy(&mut self, id: Id, enemy: Enemy) {
+        self.reach
+            .requirement_map_mut()
+            .insert(id, enemy.requirement());
+        self.seed.push((id, enemy.sprite().into()));
+    }
+
+    fn placed_items(&self, maps: &[Map], logic: &Logic) -> Result<HashMap<Id, Sprite>> {
+        let placements = self
+            .seed
+            .iter()
+            .map(|(id, sprite)| (*id, sprite.kind))
+            .collect::<HashMap<_, _>>();
+
+        let mut items = HashMap::new();
+
+        for id in logic.items() {
+            let kind = match placements.get(&id) {
+                Some(kind) => *kind,
+                None => id.find_sprite(maps)?.kind,
+            };
+            items.insert(id, kind.into());
+        }
+
+        Ok(items)
+    }
+
+    pub fn finish(self, maps: &[Map], logic: &Logic) -> Result<(Seed, ItemSpoiler)> {
+        let mut randomized = maps.to_vec();
+        self.seed.apply(&mut randomized)?;
+        let spoiler = ItemSpoiler::new(maps, &randomized, logic, &self.reach, self.seed.start)?;
+
+        Ok((self.seed, spoiler))
     }
 
     fn random_item(&mut self) -> Result<Sprite> {
         let sprite = self.pool.choose_remove(&mut self.rng);
 
         if matches!(sprite, Sprite::Gear(_)) {
-            self.update_reach();
+            self.update_reach()?;
 
             if self.reach_cache.is_empty() {
                 Err(format!("failed to safely place {sprite:?}"))?;
@@ -115,8 +476,10 @@ impl<'logic> Generator<'logic> {
         Ok(sprite)
     }
 
-    fn update_reach(&mut self) {
-        self.reach_cache = self.reach.reach(&self.pool)
+    fn update_reach(&mut self) -> Result<()> {
+        self.reach_cache = self.reach.reach(&self.pool)?;
+
+        Ok(())
     }
 
     fn fill_unreachable(&mut self) -> Result<()> {
@@ -137,30 +500,76 @@ impl<'logic> Generator<'logic> {
         self.seed.push((location, sprite.into()));
 
         if let Sprite::Gear(gear) = sprite {
-            self.spoiler.gear.insert(gear, location);
+            self.gear.insert(gear, location);
         }
     }
 
-    #[cfg(debug_assertions)]
-    pub fn validate_seed(&self, maps: &[Map], logic: &Logic) -> Result<()> {
-        fn pool_without(sprite: Sprite, maps: &[Map], logic: &Logic) -> Pool {
-            let mut pool = Pool::new(logic, maps);
-            let index = pool.iter().position(|item| *item == sprite).unwrap();
-            pool.swap_remove(index);
-            pool
+    /// Checks that every piece of gear is reachable without itself, every reachable location can be collected
+    /// and, with decoupled transfers, no area strands the player along the way
+    pub fn validate_seed(&self, maps: &[Map], logic: &Logic, settings: &Settings) -> Result<()> {
+        let plando = &settings.plando;
+
+        fn pool_without(
+            sprite: Sprite,
+            maps: &[Map],
+            logic: &Logic,
+            plando: &Plando,
+        ) -> Result<Pool> {
+            let mut pool = Pool::new(logic, maps, plando)?;
+            // Locked gear is not part of the pool to begin with
+            if let Some(index) = pool.iter().position(|item| *item == sprite) {
+                pool.swap_remove(index);
+            }
+            Ok(pool)
+        }
+
+        for (&gear, id) in &self.gear {
+            let pool = pool_without(Sprite::Gear(gear), maps, logic, plando)?;
+            if !self.reach.reach(&pool)?.contains(id) {
+                Err(format!("{gear:?} at {id} is unreachable without itself"))?;
+            }
+        }
+
+        let items = self.placed_items(maps, logic)?;
+        if let Some(id) = self.uncollected(&items)?.first() {
+            Err(format!("the item at {id} can't be collected"))?;
         }
 
-        for (&gear, id) in &self.spoiler.gear {
-            let pool = pool_without(Sprite::Gear(gear), maps, logic);
-            let reach = self.reach.reach(&pool);
-            assert!(reach.contains(id));
+        if settings.transfers && settings.transfer_mode == TransferMode::Decoupled {
+            if let Some(area) = self.reach.stranding_areas(&items)?.first() {
+                Err(format!(
+                    "\"{area}\" has no way back to \"{}\"",
+                    self.reach.start()
+                ))?;
+            }
         }
 
         Ok(())
     }
 }
 
-trait BlindShuffle: Sized {
+/// Logic for a rom that has already been randomized, with its doors, enemies and transfers where they are
+pub fn randomized_reach<'logic>(
+    randomized: &[Map],
+    logic: &'logic Logic,
+    settings: &Settings,
+    start: &'logic str,
+) -> Result<Reach<'logic>> {
+    let mut requirement_map = RequirementMap::new(logic);
+    Door::keep_vanilla(randomized, &mut requirement_map)?;
+    Enemy::keep_vanilla(randomized, &mut requirement_map)?;
+    let logic_transfers = vanilla_transfers(randomized, logic)?;
+
+    Ok(Reach::new(
+        logic,
+        start,
+        logic_transfers,
+        requirement_map,
+        settings,
+    )?)
+}
+
+trait BlindShuffle: Copy {
     const EXPECTED: &str;
 
     fn match_sprite(sprite: Sprite) -> Option<Self>;
@@ -173,15 +582,38 @@ trait BlindShuffle: Sized {
         })
     }
 
-    fn map(id: Id, maps: &[Map]) -> Self {
-        let sprite = id.expect_sprite(maps).kind.into();
-        Self::match_sprite(sprite)
-            .unwrap_or_else(|| panic!("unexpected {sprite:?} as {} {id}", Self::EXPECTED))
+    fn map(id: Id, maps: &[Map]) -> StdResult<Self, RandoError> {
+        let sprite = id.find_sprite(maps)?.kind.into();
+        Self::match_sprite(sprite).ok_or(RandoError::UnexpectedSprite {
+            id,
+            sprite,
+            expected: Self::EXPECTED,
+        })
     }
 
     fn requirement(&self) -> Vec<Vec<Sprite>>;
 
+    /// The key used up when getting past this
+    fn consumed_key(self) -> Option<Collectible> {
+        None
+    }
+
     fn sprite(self) -> Sprite;
+
+    fn keep_vanilla(
+        maps: &[Map],
+        requirement_map: &mut RequirementMap,
+    ) -> StdResult<(), RandoError> {
+        for id in Self::iter(maps) {
+            let kind = Self::map(id, maps)?;
+            requirement_map.insert(id, kind.requirement());
+            if let Some(key) = kind.consumed_key() {
+                requirement_map.insert_door_key(id, key);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl BlindShuffle for Door {
@@ -198,6 +630,13 @@ impl BlindShuffle for Door {
         vec![vec![self.key()]]
     }
 
+    fn consumed_key(self) -> Option<Collectible> {
+        match self.key() {
+            Sprite::Collectible(key) => Some(key),
+            _ => None,
+        }
+    }
+
     fn sprite(self) -> Sprite {
         Sprite::Door(self)
     }
@@ -232,25 +671,58 @@ impl Seed {
     fn blind_shuffle<T>(
         &mut self,
         maps: &[Map],
+        logic: &Logic,
         requirement_map: &mut RequirementMap,
         rng: &mut Pcg64Mcg,
-    ) where
+    ) -> StdResult<(), RandoError>
+    where
         T: BlindShuffle,
     {
         let ids = T::iter(maps).collect::<Vec<_>>();
-        let mut id_kinds = ids.iter().map(|id| T::map(*id, maps)).collect::<Vec<_>>();
-        id_kinds.shuffle(rng);
+        let mut id_kinds = ids
+            .iter()
+            .map(|id| T::map(*id, maps))
+            .collect::<StdResult<Vec<_>, _>>()?;
+        shuffle_by_footprint(&mut id_kinds, rng);
 
         requirement_map.reserve(id_kinds.len());
         self.reserve(id_kinds.len());
 
         for (kind, id) in id_kinds.into_iter().zip(ids) {
             requirement_map.insert(id, kind.requirement());
+            if let Some(key) = kind.consumed_key() {
+                requirement_map.insert_door_key(id, key);
+            }
 
-            if !id.is_excluded() {
+            if !logic.is_excluded(id) {
                 self.push((id, kind.sprite().into()));
             }
         }
+
+        Ok(())
+    }
+}
+
+// Only kinds taking up the same amount of tiles can be swapped safely
+fn shuffle_by_footprint<T: BlindShuffle>(kinds: &mut [T], rng: &mut Pcg64Mcg) {
+    let mut footprints = IndexMap::<_, Vec<_>>::new();
+    for (index, kind) in kinds.iter().enumerate() {
+        footprints
+            .entry(kind.sprite().tile_size())
+            .or_default()
+            .push(index);
+    }
+
+    for indices in footprints.values() {
+        let mut shuffled = indices
+            .iter()
+            .map(|index| kinds[*index])
+            .collect::<Vec<_>>();
+        shuffled.shuffle(rng);
+
+        for (index, kind) in indices.iter().zip(shuffled) {
+            kinds[*index] = kind;
+        }
     }
 }
 
@@ -260,7 +732,12 @@ mod tests {
 
     use itertools::Itertools;
 
-    use crate::{rando::generate, rom::RomReader};
+    use crate::{
+        rando::{
+            generate, plando::Item, recover_spoiler, FillAlgorithm, GameMode, KeyLogic, Spoiler,
+        },
+        rom::RomReader,
+    };
 
     use super::*;
 
@@ -268,8 +745,20 @@ mod tests {
     fn determinism() {
         let (maps, logic) = test_logic();
 
-        let first = generate(&maps, &logic, Some("seed".to_string())).unwrap();
-        let second = generate(&maps, &logic, Some("seed".to_string())).unwrap();
+        let first = generate(
+            &maps,
+            &logic,
+            &Settings::default(),
+            Some("seed".to_string()),
+        )
+        .unwrap();
+        let second = generate(
+            &maps,
+            &logic,
+            &Settings::default(),
+            Some("seed".to_string()),
+        )
+        .unwrap();
 
         assert_eq!(first, second);
     }
@@ -278,10 +767,27 @@ mod tests {
     fn completability() {
         let (maps, logic) = test_logic();
 
-        for seed in 0..100 {
-            if let Err(err) = generate(&maps, &logic, Some(seed.to_string())) {
-                panic!("{seed} failed: {err}");
-            }
+        let variants = [
+            Settings::default(),
+            Settings {
+                enemy_mode: EnemyMode::Progressive,
+                ..Settings::default()
+            },
+            Settings {
+                transfer_mode: TransferMode::Decoupled,
+                ..Settings::default()
+            },
+            Settings {
+                fill_algorithm: FillAlgorithm::Forward,
+                ..Settings::default()
+            },
+            Settings {
+                key_logic: KeyLogic::Counted,
+                ..Settings::default()
+            },
+        ];
+        for settings in &variants {
+            completable(&maps, &logic, settings, 100);
         }
     }
 
@@ -289,7 +795,13 @@ mod tests {
     fn enough_items() {
         let (maps, logic) = test_logic();
 
-        let (seed, _) = generate(&maps, &logic, Some("seed".to_string())).unwrap();
+        let (seed, _) = generate(
+            &maps,
+            &logic,
+            &Settings::default(),
+            Some("seed".to_string()),
+        )
+        .unwrap();
 
         let mut item_counts = HashMap::<Collectible, u16>::new();
         let mut door_counts = HashMap::<Door, u16>::new();
@@ -317,7 +829,13 @@ mod tests {
     fn no_duplicate_placements() {
         let (maps, logic) = test_logic();
 
-        let (seed, _) = generate(&maps, &logic, Some("seed".to_string())).unwrap();
+        let (seed, _) = generate(
+            &maps,
+            &logic,
+            &Settings::default(),
+            Some("seed".to_string()),
+        )
+        .unwrap();
 
         let duplicates = seed
             .placements
@@ -332,12 +850,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vanilla_settings() {
+        let (maps, logic) = test_logic();
+
+        let settings = Settings {
+            transfers: false,
+            doors: false,
+            enemies: false,
+            items: false,
+            ..Settings::default()
+        };
+        let (seed, _) = generate(&maps, &logic, &settings, Some("seed".to_string())).unwrap();
+
+        assert!(seed.placements.is_empty());
+    }
+
+    #[test]
+    fn balancing() {
+        let (maps, logic) = test_logic();
+
+        let settings = Settings {
+            balancing: Balancing {
+                bias: SphereBias::Late,
+                min_sphere: [(Gear::WindRing, 1)].into_iter().collect(),
+            },
+            ..Settings::default()
+        };
+        for (_, spoiler) in completable(&maps, &logic, &settings, 20) {
+            assert!(spoiler.items.spheres.gear[&Gear::WindRing] >= 1);
+        }
+    }
+
+    #[test]
+    fn random_start() {
+        let (maps, logic) = test_logic();
+
+        let settings = Settings {
+            random_start: true,
+            ..Settings::default()
+        };
+        for (seed, spoiler) in completable(&maps, &logic, &settings, 20) {
+            assert!(seed.start.is_some());
+            assert_eq!(seed.start, spoiler.items.start.position);
+
+            let mut randomized = maps.clone();
+            seed.apply(&mut randomized).unwrap();
+            let start = Some(spoiler.items.start.area.as_str());
+            let recovered = recover_spoiler(&maps, &randomized, &logic, &settings, start).unwrap();
+            assert_eq!(recovered, spoiler.items);
+        }
+    }
+
+    #[test]
+    fn recovered_spoiler() {
+        let (maps, logic) = test_logic();
+
+        let settings = Settings::default();
+        let (seed, spoiler) = generate(&maps, &logic, &settings, Some("seed".to_string())).unwrap();
+        let mut randomized = maps.clone();
+        seed.apply(&mut randomized).unwrap();
+
+        let recovered = recover_spoiler(&maps, &randomized, &logic, &settings, None).unwrap();
+        assert_eq!(recovered, spoiler.items);
+    }
+
+    #[test]
+    fn plando() {
+        let (maps, logic) = test_logic();
+
+        let location = logic.items().next().unwrap();
+        let mut settings = Settings::default();
+        settings
+            .plando
+            .placements
+            .insert(location, Item(Sprite::Gear(Gear::WindRing)));
+        settings
+            .plando
+            .starting_items
+            .push(Item(Sprite::Gear(Gear::LavaCharm)));
+
+        let (seed, spoiler) = generate(&maps, &logic, &settings, Some("seed".to_string())).unwrap();
+
+        assert!(seed
+            .placements
+            .contains(&(location, Sprite::Gear(Gear::WindRing).into())));
+        assert_eq!(spoiler.items.gear[&Gear::WindRing], location);
+    }
+
+    #[test]
+    fn enemy_footprints() {
+        let (maps, logic) = test_logic();
+
+        let (seed, _) = generate(
+            &maps,
+            &logic,
+            &Settings::default(),
+            Some("seed".to_string()),
+        )
+        .unwrap();
+
+        for (id, sprite) in seed.placements {
+            if let Sprite::Enemy(enemy) = sprite.kind.into() {
+                let vanilla = Enemy::map(id, &maps).unwrap();
+                assert_eq!(
+                    enemy.sprite().tile_size(),
+                    vanilla.sprite().tile_size(),
+                    "{id}"
+                );
+            }
+        }
+    }
+
+    /// Generates the seeds "0" up to `seeds` and fails if any of them can't be generated
+    fn completable(
+        maps: &[Map],
+        logic: &Logic,
+        settings: &Settings,
+        seeds: u32,
+    ) -> Vec<(Seed, Spoiler)> {
+        (0..seeds)
+            .map(|seed| {
+                generate(maps, logic, settings, Some(seed.to_string()))
+                    .unwrap_or_else(|err| panic!("{settings:?}: seed {seed} failed: {err}"))
+            })
+            .collect()
+    }
+
     fn test_logic() -> (Vec<Map>, Logic) {
         let mut rom = RomReader::open("Roms/main.hsrom".into()).unwrap();
         let mut maps = Map::parse_all(&mut rom).unwrap();
         maps.retain(Map::is_hardcore);
-        let mut logic = Logic::parse().unwrap();
-        logic.purge_doors(&maps);
+        let mut logic = Logic::parse("rando/logic.json", GameMode::Hardcore).unwrap();
+        logic.purge_doors(&maps).unwrap();
 
         (maps, logic)
     }
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64Mcg;
+use rand_seeder::Seeder;
+
+use crate::map::Gear;
+
+use super::{Logic, Spoiler};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Hint {
+    /// A piece of gear is somewhere in an area
+    Location { gear: Gear, area: String },
+    /// An area holds no gear and doesn't have to be passed through to collect any
+    DeadEnd { area: String },
+}
+
+/// Picks up to `count` hints, alternating between the kinds. The same spoiler always results in the same hints
+pub fn hints(spoiler: &Spoiler, logic: &Logic, count: usize) -> Vec<Hint> {
+    let mut rng: Pcg64Mcg = Seeder::from(format!("{} hints", spoiler.seed)).into_rng();
+    let items = &spoiler.items;
+
+    let mut locations = items
+        .gear
+        .iter()
+        .filter_map(|(gear, id)| {
+            let area = items.placements.get(id)?.area.clone()?;
+            Some(Hint::Location { gear: *gear, area })
+        })
+        .collect::<Vec<_>>();
+
+    let gear_areas = locations
+        .iter()
+        .filter_map(|hint| match hint {
+            Hint::Location { area, .. } => Some(area),
+            Hint::DeadEnd { .. } => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut dead_ends = logic
+        .areas
+        .iter()
+        .filter(|(name, area)| {
+            name.as_str() != items.start.area
+                && !area.items.is_empty()
+                && !gear_areas.contains(name)
+                && !items.required_areas.contains(name)
+        })
+        .map(|(name, _)| Hint::DeadEnd { area: name.clone() })
+        .collect::<Vec<_>>();
+
+    locations.shuffle(&mut rng);
+    dead_ends.shuffle(&mut rng);
+
+    locations
+        .into_iter()
+        .interleave(dead_ends)
+        .take(count)
+        .collect()
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Location { gear, area } => {
+                write!(f, "The {} is in {area}", spaced(&format!("{gear:?}")))
+            }
+            Self::DeadEnd { area } => write!(f, "{area} is a dead end"),
+        }
+    }
+}
+
+/// "LavaCharm" -> "Lava Charm"
+fn spaced(name: &str) -> String {
+    let mut spaced = String::new();
+
+    for (index, char) in name.char_indices() {
+        if index > 0 && char.is_uppercase() {
+            spaced.push(' ');
+        }
+        spaced.push(char);
+    }
+
+    spaced
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        map::Map,
+        rando::{generate, GameMode, Settings},
+        rom::RomReader,
+    };
+
+    use super::*;
+
+    #[test]
+    fn truthful_hints() {
+        let mut rom = RomReader::open("Roms/main.hsrom".into()).unwrap();
+        let mut maps = Map::parse_all(&mut rom).unwrap();
+        maps.retain(Map::is_hardcore);
+        let mut logic = Logic::parse("rando/logic.json", GameMode::Hardcore).unwrap();
+        logic.purge_doors(&maps).unwrap();
+
+        let (_, spoiler) = generate(
+            &maps,
+            &logic,
+            &Settings::default(),
+            Some("seed".to_string()),
+        )
+        .unwrap();
+        let items = &spoiler.items;
+
+        for hint in hints(&spoiler, &logic, usize::MAX) {
+            match hint {
+                Hint::Location { gear, area } => {
+                    let id = items.gear[&gear];
+                    assert_eq!(items.placements[&id].area.as_ref(), Some(&area));
+                }
+                Hint::DeadEnd { area } => {
+                    assert!(!items.required_areas.contains(&area));
+                    assert!(items
+                        .gear
+                        .values()
+                        .all(|id| items.placements[id].area.as_ref() != Some(&area)));
+                }
+            }
+        }
+    }
+}
@@ -1,147 +1,41 @@
-use std::{
-    error::Error,
-    fmt::{self, Debug, Display},
-    str::{FromStr, SplitN},
-};
+use std::result::Result as StdResult;
 
-use serde::{Deserialize, Serialize};
+use crate::map::{Map, SpriteData};
 
-use crate::{
-    map::{Map, SpriteData},
-    Result,
-};
+pub use crate::map::Id;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(try_from = "String", into = "String")]
-pub struct Id {
-    pub map: u8,
-    pub x: usize,
-    pub y: usize,
-}
-
-impl Id {
-    pub const fn new(map: u8, x: usize, y: usize) -> Self {
-        Self { map, x, y }
-    }
+use super::RandoError;
 
-    pub const fn is_excluded(self) -> bool {
-        const INNER_CASTLE_GUARD_LEFT: Id = Id::new(43, 45, 56);
-        const INNER_CASTLE_GUARD_RIGHT: Id = Id::new(43, 46, 56);
-        const FINAL_TRANSFER_LEFT: Id = Id::new(43, 31, 1);
-        const FINAL_TRANSFER_RIGHT: Id = Id::new(43, 32, 1);
-        const FINAL_GUARD_LEFT: Id = Id::new(43, 31, 3);
-        const FINAL_GUARD_RIGHT: Id = Id::new(43, 32, 3);
-
-        matches!(
-            self,
-            INNER_CASTLE_GUARD_LEFT
-                | INNER_CASTLE_GUARD_RIGHT
-                | FINAL_TRANSFER_LEFT
-                | FINAL_TRANSFER_RIGHT
-                | FINAL_GUARD_LEFT
-                | FINAL_GUARD_RIGHT
-        )
-    }
+/// Looks up what an [`Id`] refers to
+pub trait FindId {
+    fn find_map(self, maps: &[Map]) -> StdResult<&Map, RandoError>;
+    fn find_map_mut(self, maps: &mut [Map]) -> StdResult<&mut Map, RandoError>;
+    fn find_sprite(self, maps: &[Map]) -> StdResult<&SpriteData, RandoError>;
+    fn find_sprite_mut(self, maps: &mut [Map]) -> StdResult<&mut SpriteData, RandoError>;
+}
 
-    pub fn expect_map(self, maps: &[Map]) -> &Map {
+impl FindId for Id {
+    fn find_map(self, maps: &[Map]) -> StdResult<&Map, RandoError> {
         maps.iter()
             .find(|m| m.identifier == self.map)
-            .unwrap_or_else(|| self.unknown_map_identifier())
+            .ok_or(RandoError::UnknownMap(self))
     }
 
-    pub fn expect_map_mut(self, maps: &mut [Map]) -> &mut Map {
+    fn find_map_mut(self, maps: &mut [Map]) -> StdResult<&mut Map, RandoError> {
         maps.iter_mut()
             .find(|m| m.identifier == self.map)
-            .unwrap_or_else(|| self.unknown_map_identifier())
-    }
-
-    pub fn sprite(self, maps: &[Map]) -> Option<&SpriteData> {
-        self.expect_map(maps).sprite(self.x, self.y)
-    }
-
-    pub fn expect_sprite(self, maps: &[Map]) -> &SpriteData {
-        self.sprite(maps).unwrap_or_else(|| self.failed_to_match())
-    }
-
-    pub fn sprite_mut(self, maps: &mut [Map]) -> Option<&mut SpriteData> {
-        self.expect_map_mut(maps).sprite_mut(self.x, self.y)
-    }
-
-    pub fn expect_sprite_mut(self, maps: &mut [Map]) -> &mut SpriteData {
-        self.sprite_mut(maps)
-            .unwrap_or_else(|| self.failed_to_match())
-    }
-
-    fn unknown_map_identifier(self) -> ! {
-        panic!("unknown map identifier {}", self.map)
-    }
-
-    fn failed_to_match(self) -> ! {
-        panic!("failed to match {self} to map")
-    }
-}
-
-impl Display for Id {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.map, self.x, self.y)
-    }
-}
-
-impl From<Id> for String {
-    fn from(value: Id) -> Self {
-        value.to_string()
+            .ok_or(RandoError::UnknownMap(self))
     }
-}
 
-impl Debug for Id {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Display::fmt(self, f)
+    fn find_sprite(self, maps: &[Map]) -> StdResult<&SpriteData, RandoError> {
+        self.find_map(maps)?
+            .sprite(self.x, self.y)
+            .ok_or(RandoError::MissingSprite(self))
     }
-}
-
-impl FromStr for Id {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self> {
-        fn parse_part<T>(parts: &mut SplitN<char>, s: &str) -> Result<T>
-        where
-            T: FromStr,
-            T::Err: Display,
-        {
-            let part = parts
-                .next()
-                .ok_or_else(|| "expected string with format <map>.<x>.<y>".to_string())?;
-            let part = part
-                .parse()
-                .map_err(|err| format!("invalid part \"{part}\" in \"{s}\": {err}"))?;
-            Ok(part)
-        }
-
-        let mut parts = s.splitn(4, '.');
-        let map = parse_part(&mut parts, s)?;
-        let x = parse_part(&mut parts, s)?;
-        let y = parse_part(&mut parts, s)?;
-
-        if let Some(remaining) = parts.next() {
-            Err(format!("trailing symbols \"{remaining}\" in \"{s}\""))?;
-        }
-
-        Ok(Id { map, x, y })
-    }
-}
-
-impl TryFrom<&str> for Id {
-    type Error = <Id as FromStr>::Err;
-
-    fn try_from(value: &str) -> Result<Self> {
-        value.parse()
-    }
-}
-
-impl TryFrom<String> for Id {
-    type Error = <Id as FromStr>::Err;
 
-    fn try_from(value: String) -> Result<Self> {
-        value.parse()
+    fn find_sprite_mut(self, maps: &mut [Map]) -> StdResult<&mut SpriteData, RandoError> {
+        self.find_map_mut(maps)?
+            .sprite_mut(self.x, self.y)
+            .ok_or(RandoError::MissingSprite(self))
     }
 }
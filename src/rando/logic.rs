@@ -1,6 +1,8 @@
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
     ops::{Deref, DerefMut},
+    path::Path,
     result::Result as StdResult,
 };
 
@@ -13,37 +15,176 @@ use serde::{
 use strum::{EnumDiscriminants, EnumString, VariantArray};
 
 use crate::{
-    helpers::file_open,
+    helpers,
     map::{Collectible, Door, Gear, Map, Sprite},
     Result,
 };
 
-use super::{id::Id, pool::Pool};
+use super::{
+    code::logic_hash,
+    id::{FindId, Id},
+    pool::Pool,
+    GameMode, KeyLogic, RandoError, Settings, TrickDifficulty, Tricks,
+};
 
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Logic {
+    #[serde(default)]
+    pub mode: GameMode,
+    #[serde(default)]
+    pub excluded: HashSet<Id>,
     pub areas: IndexMap<String, Area>,
+    /// Named requirements which areas can refer to with "@<name>"
+    #[serde(default)]
+    pub macros: IndexMap<String, Requirements>,
+    /// Tricks which areas can refer to with "Trick.<name>", only met if enabled in the settings
+    #[serde(default)]
+    pub tricks: IndexMap<String, Trick>,
+    #[serde(skip)]
+    pub(super) hash: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Trick {
+    pub difficulty: TrickDifficulty,
+    #[serde(default)]
+    pub description: String,
 }
 
 impl Logic {
-    pub fn parse() -> Result<Self> {
-        let file = file_open("rando/logic.json")?;
-        let logic = serde_json::from_reader(file)?;
+    pub fn parse<P: AsRef<Path>>(path: P, mode: GameMode) -> Result<Self> {
+        let path = path.as_ref();
+        let json = helpers::read(path)?;
+        let mut logic: Logic = serde_json::from_slice(&json)?;
+        logic.hash = logic_hash(&serde_json::from_slice(&json)?);
+
+        if logic.mode != mode {
+            Err(format!(
+                "\"{}\" contains logic for {:?} mode, expected {mode:?} mode",
+                path.display(),
+                logic.mode
+            ))?;
+        }
+
+        logic.check_references()?;
+
         Ok(logic)
     }
 
-    pub fn purge_doors(&mut self, maps: &[Map]) {
+    fn check_references(&self) -> Result<()> {
+        for (name, area) in &self.areas {
+            let requirements = area.items.values().chain(area.paths.values());
+            for reference in requirements.flat_map(macro_references) {
+                if !self.macros.contains_key(reference) {
+                    Err(format!(
+                        "area \"{name}\" uses unknown macro \"@{reference}\""
+                    ))?;
+                }
+            }
+        }
+
+        for name in self.macros.keys() {
+            self.check_macro(name, &mut vec![])?;
+        }
+
+        let requirements = self
+            .areas
+            .values()
+            .flat_map(|area| area.items.values().chain(area.paths.values()))
+            .chain(self.macros.values());
+        for requirement in requirements.flatten().flatten() {
+            if let Requirement::Trick(name) = requirement {
+                self.get_trick(name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_macro<'a>(&'a self, name: &'a str, stack: &mut Vec<&'a str>) -> Result<()> {
+        if stack.contains(&name) {
+            Err(format!(
+                "macro \"@{name}\" refers back to itself through {}",
+                stack.iter().format(" -> ")
+            ))?;
+        }
+
+        let requirements = self
+            .macros
+            .get(name)
+            .ok_or_else(|| format!("unknown macro \"@{name}\""))?;
+
+        stack.push(name);
+        for reference in macro_references(requirements) {
+            self.check_macro(reference, stack)?;
+        }
+        stack.pop();
+
+        Ok(())
+    }
+
+    fn get_macro(&self, name: &str) -> StdResult<&Requirements, RandoError> {
+        self.macros
+            .get(name)
+            .ok_or_else(|| RandoError::UnknownMacro(name.to_string()))
+    }
+
+    fn get_trick(&self, name: &str) -> StdResult<&Trick, RandoError> {
+        self.tricks
+            .get(name)
+            .ok_or_else(|| RandoError::UnknownTrick(name.to_string()))
+    }
+
+    /// Names of the tricks `tricks` enables, in the order of the logic file
+    pub fn enabled_tricks(&self, tricks: &Tricks) -> StdResult<IndexSet<&str>, RandoError> {
+        for name in &tricks.enabled {
+            self.get_trick(name)?;
+        }
+
+        Ok(self
+            .tricks
+            .iter()
+            .filter(|(name, trick)| {
+                tricks.enabled.contains(name)
+                    || tricks
+                        .difficulty
+                        .is_some_and(|difficulty| trick.difficulty <= difficulty)
+            })
+            .map(|(name, _)| name.as_str())
+            .collect())
+    }
+
+    /// Identifies the logic file, share codes only work with the same logic
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn is_excluded(&self, id: Id) -> bool {
+        self.excluded.contains(&id)
+    }
+
+    pub fn purge_doors(&mut self, maps: &[Map]) -> StdResult<(), RandoError> {
         for area in self.areas.values_mut() {
-            area.items.retain(|item, _| {
-                let sprite = item.expect_sprite(maps);
-                match sprite.kind.into() {
-                    Sprite::Collectible(_) | Sprite::Gear(_) => true,
-                    Sprite::Door(Door::Gold | Door::Silver | Door::Boulder) => false,
-                    other => panic!("unexpected {other:?} as item {item}"),
+            let mut doors = vec![];
+
+            for item in area.items.keys() {
+                match item.find_sprite(maps)?.kind.into() {
+                    Sprite::Collectible(_) | Sprite::Gear(_) => {}
+                    Sprite::Door(Door::Gold | Door::Silver | Door::Boulder) => doors.push(*item),
+                    sprite => Err(RandoError::UnexpectedSprite {
+                        id: *item,
+                        sprite,
+                        expected: "item",
+                    })?,
                 }
-            });
+            }
+
+            area.items.retain(|item, _| !doors.contains(item));
         }
+
+        Ok(())
     }
 
     pub fn items(&self) -> impl Iterator<Item = Id> + use<'_> {
@@ -53,7 +194,7 @@ impl Logic {
             .copied()
     }
 
-    pub fn transfer_groups(&self) -> Vec<Vec<(&String, Id)>> {
+    pub fn transfer_groups(&self) -> StdResult<Vec<Vec<(&String, Id)>>, RandoError> {
         let mut remaining = self.areas.keys().collect::<IndexSet<_>>();
         let mut groups = vec![];
 
@@ -67,7 +208,7 @@ impl Logic {
                 }
                 remaining.swap_remove(path);
 
-                let area = self.get_area(path);
+                let area = self.get_area(path)?;
                 paths.extend(area.paths.keys());
                 group.extend(area.transfers.iter().map(|transfer| (path, *transfer)));
             }
@@ -77,38 +218,270 @@ impl Logic {
             }
         }
 
-        groups
+        Ok(groups)
+    }
+
+    pub fn area_names(&self) -> HashMap<Id, &String> {
+        let mut area_names = HashMap::new();
+
+        for (name, area) in &self.areas {
+            for id in area.items.keys().chain(&area.transfers) {
+                area_names.insert(*id, name);
+            }
+        }
+
+        for (id, names) in self.requirement_references() {
+            area_names.entry(id).or_insert(names[0]);
+        }
+
+        area_names
+    }
+
+    pub fn requirement_references(&self) -> HashMap<Id, Vec<&String>> {
+        let mut references = HashMap::<_, Vec<_>>::new();
+
+        for (name, area) in &self.areas {
+            let mut ids = vec![];
+            for requirements in area.items.values().chain(area.paths.values()) {
+                self.requirement_ids(requirements, &mut ids);
+            }
+
+            for id in ids {
+                references.entry(id).or_default().push(name);
+            }
+        }
+
+        references
+    }
+
+    /// Collects the ids referenced in `requirements`, including those inside macros
+    fn requirement_ids(&self, requirements: &Requirements, ids: &mut Vec<Id>) {
+        for requirement in requirements.iter().flatten() {
+            match requirement {
+                Requirement::Id(id) => ids.push(*id),
+                Requirement::Macro(name) => {
+                    if let Some(requirements) = self.macros.get(name) {
+                        self.requirement_ids(requirements, ids);
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 
-    fn get_area(&self, path: &str) -> &Area {
+    fn get_area(&self, path: &str) -> StdResult<&Area, RandoError> {
         self.areas
             .get(path)
-            .unwrap_or_else(|| panic!("path target \"{path}\" doesn't exist"))
+            .ok_or_else(|| RandoError::UnknownArea(path.to_string()))
     }
 }
 
+#[derive(Clone)]
 pub struct Reach<'logic> {
     logic: &'logic Logic,
+    start: &'logic str,
     transfers: HashMap<Id, &'logic String>,
     requirement_map: RequirementMap,
+    locked: HashMap<Id, Sprite>,
+    starting_items: Vec<Sprite>,
+    key_logic: KeyLogic,
+    tricks: IndexSet<&'logic str>,
 }
 
 impl<'logic> Reach<'logic> {
     pub fn new(
         logic: &'logic Logic,
+        start: &'logic str,
         transfers: HashMap<Id, &'logic String>,
         requirement_map: RequirementMap,
-    ) -> Self {
-        Self {
+        settings: &Settings,
+    ) -> StdResult<Self, RandoError> {
+        let plando = &settings.plando;
+
+        Ok(Self {
             logic,
+            start,
             transfers,
             requirement_map,
+            locked: plando
+                .placements
+                .iter()
+                .map(|(id, item)| (*id, item.0))
+                .collect(),
+            starting_items: plando.starting_items.iter().map(|item| item.0).collect(),
+            key_logic: settings.key_logic,
+            tricks: logic.enabled_tricks(&settings.tricks)?,
+        })
+    }
+
+    pub fn reach(&self, pool: &Pool) -> StdResult<IndexSet<Id>, RandoError> {
+        Ok(self.traverse(pool)?.1)
+    }
+
+    /// Like `reach`, but also collects the given placements once their location is reached
+    pub fn reach_with_placements(
+        &self,
+        pool: &Pool,
+        placements: &HashMap<Id, Sprite>,
+    ) -> StdResult<IndexSet<Id>, RandoError> {
+        Ok(self.traverse_with_placements(pool, placements)?.1)
+    }
+
+    pub fn requirement_map_mut(&mut self) -> &mut RequirementMap {
+        &mut self.requirement_map
+    }
+
+    /// The area everything is reached from
+    pub fn start(&self) -> &'logic str {
+        self.start
+    }
+
+    /// Areas which can be entered at some point while collecting `items`, but have no way back to the start
+    /// with what has been collected by then
+    pub fn stranding_areas(
+        &self,
+        items: &HashMap<Id, Sprite>,
+    ) -> StdResult<Vec<&'logic str>, RandoError> {
+        let mut inventory = Pool::default();
+        let mut collected = HashSet::new();
+        let mut checked = HashSet::new();
+        let mut stranding = vec![];
+
+        loop {
+            let pool = self.with_starting_items(&inventory);
+            let (areas, reached) = self.traverse_from(self.start, &pool)?;
+
+            // More items never close a way back, so each area only needs checking when it is first entered
+            for area in areas {
+                if checked.insert(area) && !self.traverse_from(area, &pool)?.0.contains(self.start)
+                {
+                    stranding.push(area);
+                }
+            }
+
+            let mut progress = false;
+            for id in reached {
+                if let Some(sprite) = items.get(&id) {
+                    if collected.insert(id) {
+                        inventory.push(*sprite);
+                        progress = true;
+                    }
+                }
+            }
+
+            if !progress {
+                stranding.sort_unstable();
+                return Ok(stranding);
+            }
         }
     }
 
-    pub fn reach(&self, pool: &Pool) -> IndexSet<Id> {
-        let mut paths = vec!["Spawn"];
-        let mut visited = HashSet::new();
+    // Starting items are always owned and locked items are collected once their location is reached
+    fn traverse(&self, pool: &Pool) -> StdResult<(HashSet<&'logic str>, IndexSet<Id>), RandoError> {
+        self.traverse_with_placements(pool, &HashMap::new())
+    }
+
+    fn traverse_with_placements(
+        &self,
+        pool: &Pool,
+        placements: &HashMap<Id, Sprite>,
+    ) -> StdResult<(HashSet<&'logic str>, IndexSet<Id>), RandoError> {
+        let mut inventory = self.with_starting_items(pool);
+        let mut collected = HashSet::new();
+
+        loop {
+            let (visited, reached) = self.traverse_from(self.start, &inventory)?;
+            let mut progress = false;
+
+            for id in &reached {
+                if let Some(sprite) = self.locked.get(id).or_else(|| placements.get(id)) {
+                    if collected.insert(*id) {
+                        inventory.push(*sprite);
+                        progress = true;
+                    }
+                }
+            }
+
+            if !progress {
+                return Ok((visited, reached));
+            }
+        }
+    }
+
+    fn with_starting_items(&self, pool: &Pool) -> Pool {
+        let mut inventory = pool.clone();
+        inventory.extend(&self.starting_items);
+        inventory
+    }
+
+    fn traverse_from(
+        &self,
+        start: &'logic str,
+        pool: &Pool,
+    ) -> StdResult<(HashSet<&'logic str>, IndexSet<Id>), RandoError> {
+        self.traverse_avoiding(start, pool, None)
+    }
+
+    fn traverse_avoiding(
+        &self,
+        start: &'logic str,
+        pool: &Pool,
+        avoid: Option<&'logic str>,
+    ) -> StdResult<(HashSet<&'logic str>, IndexSet<Id>), RandoError> {
+        let mut closed = HashSet::new();
+
+        loop {
+            let (visited, reached) = self.traverse_closed(start, pool, avoid, &closed)?;
+
+            if self.key_logic == KeyLogic::Simple {
+                return Ok((visited, reached));
+            }
+
+            // Keys might get spent on other doors first, so unless there are enough keys for every door
+            // of a color that may be encountered, none of them can be relied upon
+            let mut doors = HashMap::<Collectible, HashSet<Id>>::new();
+            for area in &visited {
+                let area = self.logic.get_area(area)?;
+                let mut ids = vec![];
+                for requirements in area.items.values().chain(area.paths.values()) {
+                    self.logic.requirement_ids(requirements, &mut ids);
+                }
+
+                for id in ids {
+                    if let Some(key) = self.requirement_map.door_keys.get(&id) {
+                        doors.entry(*key).or_default().insert(id);
+                    }
+                }
+            }
+
+            let newly_closed = doors
+                .into_iter()
+                .filter(|(key, doors)| {
+                    let keys = pool
+                        .iter()
+                        .filter(|sprite| **sprite == Sprite::Collectible(*key))
+                        .count();
+                    !closed.contains(key) && keys < doors.len()
+                })
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>();
+
+            if newly_closed.is_empty() {
+                return Ok((visited, reached));
+            }
+            closed.extend(newly_closed);
+        }
+    }
+
+    fn traverse_closed(
+        &self,
+        start: &'logic str,
+        pool: &Pool,
+        avoid: Option<&'logic str>,
+        closed: &HashSet<Collectible>,
+    ) -> StdResult<(HashSet<&'logic str>, IndexSet<Id>), RandoError> {
+        let mut paths = vec![start];
+        let mut visited = avoid.into_iter().collect::<HashSet<_>>();
         let mut reached = IndexSet::new();
 
         while let Some(path) = paths.pop() {
@@ -117,43 +490,239 @@ impl<'logic> Reach<'logic> {
             }
             visited.insert(path);
 
-            let area = self.logic.get_area(path);
+            let area = self.logic.get_area(path)?;
 
             for (item, requirements) in &area.items {
-                if self.is_met(requirements, pool) {
+                if self.is_met(requirements, pool, closed)? {
                     reached.insert(*item);
                 }
             }
 
             for (to, requirements) in &area.paths {
-                if self.is_met(requirements, pool) {
+                if self.is_met(requirements, pool, closed)? {
                     paths.push(to);
                 }
             }
 
             for transfer in &area.transfers {
-                paths.push(self.transfers[transfer]);
+                if let Some(target) = self.transfers.get(transfer) {
+                    paths.push(target);
+                }
             }
         }
 
-        reached
+        Ok((visited, reached))
     }
 
-    fn is_met(&self, requirements: &Requirements, pool: &Pool) -> bool {
-        requirements.iter().any(|requirements| {
-            requirements.iter().all(|requirement| {
-                let items = self.requirement_map.required_items(requirement);
-                items.iter().any(|items| pool.contains_all(items))
-            })
-        })
+    pub fn explore(
+        &self,
+        items: &HashMap<Id, Sprite>,
+    ) -> StdResult<(HashSet<&'logic str>, Pool), RandoError> {
+        let mut inventory = Pool::default();
+        let mut collected = HashSet::new();
+
+        loop {
+            let (areas, reached) =
+                self.traverse_from(self.start, &self.with_starting_items(&inventory))?;
+            let mut progress = false;
+
+            for id in reached {
+                if let Some(sprite) = items.get(&id) {
+                    if collected.insert(id) {
+                        inventory.push(*sprite);
+                        progress = true;
+                    }
+                }
+            }
+
+            if !progress {
+                return Ok((areas, inventory));
+            }
+        }
+    }
+
+    pub fn spheres(&self, items: &HashMap<Id, Sprite>) -> StdResult<Vec<Vec<Id>>, RandoError> {
+        let mut inventory = Pool::default();
+        let mut collected = HashSet::new();
+        let mut spheres = vec![];
+
+        loop {
+            let sphere = self
+                .traverse_from(self.start, &self.with_starting_items(&inventory))?
+                .1
+                .into_iter()
+                .filter(|id| items.contains_key(id) && !collected.contains(id))
+                .collect::<Vec<_>>();
+
+            if sphere.is_empty() {
+                return Ok(spheres);
+            }
+
+            for id in &sphere {
+                inventory.push(items[id]);
+                collected.insert(*id);
+            }
+
+            spheres.push(sphere);
+        }
+    }
+
+    /// Groups the reachable locations by the round of collecting `placements` in which they are first reached, with `owned` available from the start
+    pub fn location_spheres(
+        &self,
+        placements: &HashMap<Id, Sprite>,
+        owned: &Pool,
+    ) -> StdResult<Vec<IndexSet<Id>>, RandoError> {
+        let mut inventory = self.with_starting_items(owned);
+        let mut reached = HashSet::new();
+        let mut spheres = vec![];
+
+        loop {
+            let sphere = self
+                .traverse_from(self.start, &inventory)?
+                .1
+                .into_iter()
+                .filter(|id| !reached.contains(id))
+                .collect::<IndexSet<_>>();
+
+            if sphere.is_empty() {
+                return Ok(spheres);
+            }
+
+            for id in &sphere {
+                if let Some(sprite) = placements.get(id) {
+                    inventory.push(*sprite);
+                }
+                reached.insert(*id);
+            }
+
+            spheres.push(sphere);
+        }
+    }
+
+    /// Areas which have to be passed through to collect all of `targets`, picking up the rest of `placements` along the way
+    pub fn required_areas(
+        &self,
+        placements: &HashMap<Id, Sprite>,
+        targets: &HashSet<Id>,
+    ) -> StdResult<Vec<&'logic str>, RandoError> {
+        let areas = self
+            .traverse_with_placements(&Pool::default(), placements)?
+            .0
+            .into_iter()
+            .sorted_unstable();
+
+        let mut required = vec![];
+        for area in areas {
+            if area != self.start && !self.collects_all(placements, targets, Some(area))? {
+                required.push(area);
+            }
+        }
+
+        Ok(required)
+    }
+
+    /// Enabled tricks without which not all of `targets` could be collected, picking up the rest of `placements` along the way
+    pub fn required_tricks(
+        &self,
+        placements: &HashMap<Id, Sprite>,
+        targets: &HashSet<Id>,
+    ) -> StdResult<Vec<&'logic str>, RandoError> {
+        let mut required = vec![];
+
+        for trick in &self.tricks {
+            let mut without = self.clone();
+            without.tricks.shift_remove(trick);
+
+            if !without.collects_all(placements, targets, None)? {
+                required.push(*trick);
+            }
+        }
+
+        Ok(required)
+    }
+
+    fn collects_all(
+        &self,
+        placements: &HashMap<Id, Sprite>,
+        targets: &HashSet<Id>,
+        avoid: Option<&'logic str>,
+    ) -> StdResult<bool, RandoError> {
+        let mut inventory = self.with_starting_items(&Pool::default());
+        let mut collected = HashSet::new();
+
+        loop {
+            let reached = self.traverse_avoiding(self.start, &inventory, avoid)?.1;
+            let mut progress = false;
+
+            for id in reached {
+                if let Some(sprite) = placements.get(&id) {
+                    if collected.insert(id) {
+                        inventory.push(*sprite);
+                        progress = true;
+                    }
+                }
+            }
+
+            if !progress {
+                return Ok(targets.is_subset(&collected));
+            }
+        }
+    }
+
+    fn is_met(
+        &self,
+        requirements: &Requirements,
+        pool: &Pool,
+        closed: &HashSet<Collectible>,
+    ) -> StdResult<bool, RandoError> {
+        for requirements in requirements {
+            let mut met = true;
+
+            for requirement in requirements {
+                let requirement_met = match requirement {
+                    Requirement::Macro(name) => {
+                        self.is_met(self.logic.get_macro(name)?, pool, closed)?
+                    }
+                    Requirement::Trick(name) => self.tricks.contains(name.as_str()),
+                    Requirement::Id(id)
+                        if self
+                            .requirement_map
+                            .door_keys
+                            .get(id)
+                            .is_some_and(|key| closed.contains(key)) =>
+                    {
+                        false
+                    }
+                    _ => self
+                        .requirement_map
+                        .required_items(requirement)?
+                        .iter()
+                        .any(|items| pool.contains_all(items)),
+                };
+                if !requirement_met {
+                    met = false;
+                    break;
+                }
+            }
+
+            if met {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
     }
 }
 
+#[derive(Clone)]
 pub struct RequirementMap {
     ring_requirement: Vec<Vec<Sprite>>,
     charm_requirement: Vec<Vec<Sprite>>,
     shield_requirement: Vec<Vec<Sprite>>,
     id_requirements: HashMap<Id, Vec<Vec<Sprite>>>,
+    /// Doors which use up the key opening them
+    door_keys: HashMap<Id, Collectible>,
 }
 
 impl RequirementMap {
@@ -163,18 +732,39 @@ impl RequirementMap {
             charm_requirement: vec![vec![Sprite::Gear(Gear::LavaCharm)]],
             shield_requirement: vec![vec![Sprite::Collectible(Collectible::Shield)]],
             id_requirements: logic.items().map(|id| (id, vec![vec![]])).collect(),
+            door_keys: HashMap::new(),
         }
     }
 
-    fn required_items(&self, requirement: &Requirement) -> &Vec<Vec<Sprite>> {
+    pub fn insert_door_key(&mut self, id: Id, key: Collectible) {
+        self.door_keys.insert(id, key);
+    }
+
+    fn required_items(
+        &self,
+        requirement: &Requirement,
+    ) -> StdResult<Cow<'_, [Vec<Sprite>]>, RandoError> {
         match requirement {
-            Requirement::Ring => &self.ring_requirement,
-            Requirement::Charm => &self.charm_requirement,
-            Requirement::Shield => &self.shield_requirement,
+            Requirement::Ring => Ok(Cow::Borrowed(self.ring_requirement.as_slice())),
+            Requirement::Charm => Ok(Cow::Borrowed(self.charm_requirement.as_slice())),
+            Requirement::Shield => Ok(Cow::Borrowed(self.shield_requirement.as_slice())),
+            Requirement::Gear(gear) => Ok(Cow::Owned(vec![vec![Sprite::Gear(*gear)]])),
+            Requirement::Count(collectible, count) => {
+                Ok(Cow::Owned(vec![vec![
+                    Sprite::Collectible(*collectible);
+                    *count
+                ]]))
+            }
             Requirement::Id(id) => self
                 .id_requirements
                 .get(id)
-                .unwrap_or_else(|| panic!("Unknown requirement {id}")),
+                .map(|items| Cow::Borrowed(items.as_slice()))
+                .ok_or(RandoError::UnknownRequirement(*id)),
+            // Only the logic knows what these stand for, see `Reach::is_met`
+            Requirement::Macro(name) => Err(RandoError::UnresolvedRequirement(format!("@{name}"))),
+            Requirement::Trick(name) => {
+                Err(RandoError::UnresolvedRequirement(format!("Trick.{name}")))
+            }
         }
     }
 }
@@ -209,6 +799,10 @@ pub enum Requirement {
     Ring,
     Charm,
     Shield,
+    Gear(Gear),
+    Count(Collectible, usize),
+    Macro(String),
+    Trick(String),
     Id(Id),
 }
 
@@ -218,11 +812,25 @@ impl RequirementDiscriminants {
             Self::Ring => "Ring",
             Self::Charm => "Charm",
             Self::Shield => "Shield",
+            Self::Gear => "<gear>",
+            Self::Count => "<collectible>:<count>",
+            Self::Macro => "@<macro>",
+            Self::Trick => "Trick.<trick>",
             Self::Id => "<map>.<x>.<y>",
         }
     }
 }
 
+fn macro_references(requirements: &Requirements) -> impl Iterator<Item = &String> {
+    requirements
+        .iter()
+        .flatten()
+        .filter_map(|requirement| match requirement {
+            Requirement::Macro(name) => Some(name),
+            _ => None,
+        })
+}
+
 impl<'de> Deserialize<'de> for Requirement {
     fn deserialize<D>(deserializer: D) -> StdResult<Self, D::Error>
     where
@@ -245,16 +853,34 @@ impl<'de> Deserialize<'de> for Requirement {
 
         let str = String::deserialize(deserializer)?;
 
-        let mut parts = str.split('.');
-        let variant = parts.next().unwrap();
+        if let Some(name) = str.strip_prefix('@') {
+            return Ok(Requirement::Macro(name.to_string()));
+        }
+        if let Some(name) = str.strip_prefix("Trick.") {
+            return Ok(Requirement::Trick(name.to_string()));
+        }
 
-        let requirement = match variant.parse() {
-            Ok(RequirementDiscriminants::Ring) => Requirement::Ring,
-            Ok(RequirementDiscriminants::Charm) => Requirement::Charm,
-            Ok(RequirementDiscriminants::Shield) => Requirement::Shield,
-            Ok(RequirementDiscriminants::Id) | Err(_) => {
-                let id = str.parse().map_err(|_| invalid_variant::<D>(&str))?;
-                Requirement::Id(id)
+        let (name, count) = match str.split_once(':') {
+            Some((name, count)) => {
+                let count = count.parse().map_err(|_| invalid_variant::<D>(&str))?;
+                (name, Some(count))
+            }
+            None => (str.as_str(), None),
+        };
+
+        let requirement = match (name, count) {
+            ("Ring", None) => Requirement::Ring,
+            ("Charm", None) => Requirement::Charm,
+            ("Shield", None) => Requirement::Shield,
+            _ => {
+                if let Ok(collectible) = name.parse() {
+                    Requirement::Count(collectible, count.unwrap_or(1))
+                } else if let (Ok(gear), None) = (name.parse(), count) {
+                    Requirement::Gear(gear)
+                } else {
+                    let id = str.parse().map_err(|_| invalid_variant::<D>(&str))?;
+                    Requirement::Id(id)
+                }
             }
         };
 
@@ -264,87 +890,103 @@ impl<'de> Deserialize<'de> for Requirement {
 
 #[cfg(test)]
 mod tests {
-    use rand_pcg::Pcg64Mcg;
-
-    use crate::{map::Things, rando::generator::Generator};
-
     use super::*;
 
     #[test]
-    fn validate_logic() {
-        use crate::rom::{Rom, RomReader};
-
-        let mut logic = Logic::parse().unwrap();
-
-        let mut valid = true;
-
-        let mut reader = RomReader::open("Roms/main.hsrom".into()).unwrap();
-        let rom = Rom::parse(&mut reader);
-
-        for (name, area) in &logic.areas {
-            let mut ids = area.items.keys().chain(&area.transfers);
-            match ids.next() {
-                None => {
-                    eprintln!("{name} has no items or transfers");
-                    valid = false;
-                }
-                Some(first) => {
-                    for id in ids {
-                        if id.map != first.map {
-                            eprintln!("{name} contains items or transfers in multiple maps: {first} and {id}");
-                            valid = false;
-                        }
-                    }
-                }
-            }
-
-            for path in area.paths.keys() {
-                if !logic.get_area(path).paths.contains_key(name) {
-                    eprintln!("{name} -> {path} exists but {path} -> {name} doesn't");
-                    valid = false;
-                }
-            }
+    fn requirement_grammar() {
+        let requirements = serde_json::from_str::<Vec<Requirement>>(
+            r#"["Ring", "WindRing", "Sword:30", "GoldKey", "@Lava", "Trick.WindSkip", "1.2.3"]"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            requirements.as_slice(),
+            [
+                Requirement::Ring,
+                Requirement::Gear(Gear::WindRing),
+                Requirement::Count(Collectible::Sword, 30),
+                Requirement::Count(Collectible::GoldKey, 1),
+                Requirement::Macro(name),
+                Requirement::Trick(trick),
+                Requirement::Id(Id { map: 1, x: 2, y: 3 }),
+            ] if name == "Lava" && trick == "WindSkip"
+        ));
+
+        for invalid in [r#""Sword:many""#, r#""WindRing:2""#, r#""Nothing""#] {
+            assert!(serde_json::from_str::<Requirement>(invalid).is_err());
         }
+    }
 
-        let maps = rom.maps.unwrap();
-
-        for map in &maps {
-            if !map.is_hardcore() {
-                continue;
-            }
-
-            for (x, y, sprite) in map.sprites_with_positions() {
-                let id = Id::new(map.identifier, x, y);
-
-                if id.is_excluded() {
-                    continue;
-                }
-
-                let mapped = match sprite.kind.into() {
-                    Sprite::Collectible(_) | Sprite::Gear(_) | Sprite::Door(_) => logic
-                        .areas
-                        .values()
-                        .flat_map(|area| area.items.keys())
-                        .contains(&id),
-                    Sprite::Things(Things::Transfer) => logic
-                        .areas
-                        .values()
-                        .flat_map(|area| &area.transfers)
-                        .contains(&id),
-                    _ => true,
-                };
+    #[test]
+    fn macro_cycles() {
+        let logic = serde_json::from_str::<Logic>(
+            r#"{
+                "areas": {"Spawn": {"paths": {"Other": [["@A"]]}}, "Other": {}},
+                "macros": {"A": [["@B"]], "B": [["Charm"], ["@A"]]}
+            }"#,
+        )
+        .unwrap();
+
+        assert!(logic.check_references().is_err());
+    }
 
-                if !mapped {
-                    eprintln!("{id} not mapped in logic");
-                    valid = false;
-                }
-            }
+    #[test]
+    fn unresolved_requirements() {
+        let logic = serde_json::from_str::<Logic>(r#"{"areas": {"Spawn": {}}}"#).unwrap();
+        let requirement_map = RequirementMap::new(&logic);
+
+        for requirement in [
+            Requirement::Macro("Lava".to_string()),
+            Requirement::Trick("WindSkip".to_string()),
+        ] {
+            assert!(requirement_map.required_items(&requirement).is_err());
         }
+    }
 
-        logic.purge_doors(&maps);
-
-        Generator::new(&maps, &logic, &mut Pcg64Mcg::new(0)).unwrap();
+    #[test]
+    fn trick_tiers() {
+        let logic = serde_json::from_str::<Logic>(
+            r#"{
+                "areas": {"Spawn": {"paths": {"Other": [["Trick.WindSkip"], ["@Skip"]]}}, "Other": {}},
+                "macros": {"Skip": [["Trick.LavaWalk", "Boots"]]},
+                "tricks": {
+                    "WindSkip": {"difficulty": "medium", "description": "Jump the gap"},
+                    "LavaWalk": {"difficulty": "expert"}
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(logic.check_references().is_ok());
+
+        let enabled = |difficulty, enabled: &[&str]| {
+            let tricks = Tricks {
+                difficulty,
+                enabled: enabled.iter().map(ToString::to_string).collect(),
+            };
+            logic
+                .enabled_tricks(&tricks)
+                .map(|tricks| tricks.into_iter().collect::<Vec<_>>())
+        };
 
-        assert!(valid);
+        assert_eq!(enabled(None, &[]).unwrap(), Vec::<&str>::new());
+        assert_eq!(
+            enabled(Some(TrickDifficulty::Easy), &[]).unwrap(),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            enabled(Some(TrickDifficulty::Hard), &[]).unwrap(),
+            ["WindSkip"]
+        );
+        assert_eq!(
+            enabled(Some(TrickDifficulty::Easy), &["LavaWalk"]).unwrap(),
+            ["LavaWalk"]
+        );
+        assert!(enabled(None, &["Nothing"]).is_err());
+
+        let unknown = serde_json::from_str::<Logic>(
+            r#"{"areas": {"Spawn": {"paths": {"Other": [["Trick.Nothing"]]}}, "Other": {}}}"#,
+        )
+        .unwrap();
+        assert!(unknown.check_references().is_err());
     }
 }
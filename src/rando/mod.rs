@@ -1,49 +1,140 @@
+mod code;
+mod cosmetics;
+mod error;
 mod generator;
+mod hints;
 mod id;
 mod logic;
+mod plando;
 mod pool;
 mod seed;
+mod settings;
 mod spoiler;
+mod stats;
 mod transfers;
+mod validate;
 mod visualize;
 
+pub use code::{parse_share_code, share_code, SharedSeed, GENERATOR_VERSION};
 use constcat::concat_slices;
+pub use cosmetics::shuffle_cosmetics;
+pub use error::RandoError;
+pub use hints::{hints, Hint};
 pub use logic::Logic;
+pub use plando::Plando;
 use rand_pcg::Pcg64Mcg;
 use rand_seeder::Seeder;
 pub use seed::Seed;
-use spoiler::ItemSpoiler;
-pub use spoiler::Spoiler;
+pub use settings::{
+    Balancing, Cosmetics, EnemyMode, FillAlgorithm, GameMode, KeyLogic, Settings, SphereBias,
+    TransferMode, TrickDifficulty, Tricks,
+};
+pub use spoiler::{ItemSpoiler, Spoiler, StartSpoiler};
+pub use stats::Stats;
 use strum::VariantNames;
+pub use transfers::start_positions;
+pub use validate::validate_logic;
 pub use visualize::Visualizer;
 
-use generator::Generator;
+use generator::{randomized_reach, Generator};
 
 use crate::{
     map::{Collectible, Door, Enemy, Gear, Map},
     Result,
 };
 
-pub fn generate(maps: &[Map], logic: &Logic, seed: Option<String>) -> Result<(Seed, Spoiler)> {
+pub fn generate(
+    maps: &[Map],
+    logic: &Logic,
+    settings: &Settings,
+    seed: Option<String>,
+) -> Result<(Seed, Spoiler)> {
+    generate_attempts(maps, logic, settings, seed).result
+}
+
+/// Reconstructs the placements and transfers of a `randomized` rom by comparing it to the vanilla `maps`
+///
+/// Whatever isn't stored in the rom is passed in: settings such as tricks in `settings`,
+/// and the area a seed with a random start begins in as `start`
+pub fn recover_spoiler(
+    maps: &[Map],
+    randomized: &[Map],
+    logic: &Logic,
+    settings: &Settings,
+    start: Option<&str>,
+) -> Result<ItemSpoiler> {
+    let (start, position) = match start {
+        None => ("Spawn", None),
+        Some(area) => {
+            let (area, position) = start_positions(maps, logic)?
+                .into_iter()
+                .find(|(name, _)| *name == area)
+                .ok_or_else(|| format!("\"{area}\" is no area a seed can start in"))?;
+            (area.as_str(), Some(position))
+        }
+    };
+    let reach = randomized_reach(randomized, logic, settings, start)?;
+
+    Ok(ItemSpoiler::new(maps, randomized, logic, &reach, position)?)
+}
+
+const MAX_ATTEMPTS: u8 = 10;
+
+/// The outcome of generating a seed, along with the attempts it took
+pub struct Attempts {
+    /// How many attempts were made, including the last one
+    pub count: u8,
+    /// Errors of the attempts which failed and were retried
+    pub failures: Vec<String>,
+    pub result: Result<(Seed, Spoiler)>,
+}
+
+/// Like [`generate`], but also reports the attempts which failed before the last one
+pub fn generate_attempts(
+    maps: &[Map],
+    logic: &Logic,
+    settings: &Settings,
+    seed: Option<String>,
+) -> Attempts {
     let rng_seed = seed.unwrap_or_else(random_seed);
     let mut rng = Seeder::from(&rng_seed).into_rng();
+    let mut failures = vec![];
 
-    for attempt in 0_u8..10 {
-        match try_generate(maps, logic, &mut rng) {
+    for attempt in 1..=MAX_ATTEMPTS {
+        match try_generate(maps, logic, settings, &mut rng) {
             Ok((seed, spoiler)) => {
-                let spoiler = Spoiler {
-                    seed: rng_seed,
-                    items: spoiler,
+                let result = share_code(settings, logic, &rng_seed).map(|code| {
+                    let spoiler = Spoiler {
+                        code,
+                        seed: rng_seed,
+                        settings: settings.clone(),
+                        items: spoiler,
+                    };
+                    (seed, spoiler)
+                });
+                return Attempts {
+                    count: attempt,
+                    failures,
+                    result,
                 };
-                return Ok((seed, spoiler));
             }
-            Err(err) => {
-                eprintln!("seed attempt {attempt} failed - {err}");
+            // Problems with the logic or maps won't go away by retrying
+            Err(err) if err.is::<RandoError>() => {
+                return Attempts {
+                    count: attempt,
+                    failures,
+                    result: Err(err),
+                };
             }
+            Err(err) => failures.push(err.to_string()),
         }
     }
 
-    Err("all attempts failed")?
+    Attempts {
+        count: MAX_ATTEMPTS,
+        failures,
+        result: Err("all attempts failed".into()),
+    }
 }
 
 fn random_seed() -> String {
@@ -54,15 +145,29 @@ fn random_seed() -> String {
     Generator::new(ADJECTIVES, NOUNS).next().unwrap()
 }
 
-fn try_generate(maps: &[Map], logic: &Logic, rng: &mut Pcg64Mcg) -> Result<(Seed, ItemSpoiler)> {
-    let mut generator = Generator::new(maps, logic, rng)?;
+fn try_generate(
+    maps: &[Map],
+    logic: &Logic,
+    settings: &Settings,
+    rng: &mut Pcg64Mcg,
+) -> Result<(Seed, ItemSpoiler)> {
+    let mut generator = Generator::new(maps, logic, settings, rng)?;
+
+    match settings.fill_algorithm {
+        FillAlgorithm::Forward => {
+            while !generator.finished() {
+                generator.place_item()?;
+            }
+        }
+        FillAlgorithm::Assumed => generator.assumed_fill()?,
+    }
+    generator.check_min_spheres()?;
 
-    while !generator.finished() {
-        generator.place_item()?;
+    if settings.enemies && settings.enemy_mode == EnemyMode::Progressive {
+        generator.progressive_enemy_shuffle(maps, logic)?;
     }
 
-    #[cfg(debug_assertions)]
-    generator.validate_seed(maps, logic)?;
+    generator.validate_seed(maps, logic, settings)?;
 
-    Ok(generator.finish())
+    generator.finish(maps, logic)
 }
@@ -0,0 +1,55 @@
+use std::fmt::{self, Display};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::map::{Collectible, Gear, Sprite};
+
+use super::id::Id;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Plando {
+    /// Items placed at fixed locations, they are taken out of the shuffled pool
+    pub placements: IndexMap<Id, Item>,
+    /// Items the player starts out with, in addition to the ones in the world
+    pub starting_items: Vec<Item>,
+}
+
+/// A Collectible or Gear, written by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Item(pub Sprite);
+
+impl TryFrom<String> for Item {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let sprite = match value.parse::<Collectible>() {
+            Ok(collectible) => Sprite::Collectible(collectible),
+            Err(_) => Sprite::Gear(
+                value
+                    .parse::<Gear>()
+                    .map_err(|_| format!("unknown item \"{value}\""))?,
+            ),
+        };
+
+        Ok(Self(sprite))
+    }
+}
+
+impl From<Item> for String {
+    fn from(value: Item) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Sprite::Collectible(collectible) => write!(f, "{collectible:?}"),
+            Sprite::Gear(gear) => write!(f, "{gear:?}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
@@ -8,19 +8,19 @@ use crate::{
     Result,
 };
 
-use super::logic::Logic;
+use super::{id::FindId, logic::Logic, Plando};
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Pool {
     items: Vec<Sprite>,
 }
 
 impl Pool {
-    pub fn new(logic: &Logic, maps: &[Map]) -> Self {
+    pub fn new(logic: &Logic, maps: &[Map], plando: &Plando) -> Result<Self> {
         let mut items = vec![];
 
         for item in logic.items() {
-            let sprite = item.expect_sprite(maps).kind.into();
+            let sprite = item.find_sprite(maps)?.kind.into();
 
             debug_assert!(
                 matches!(sprite, Sprite::Collectible(_) | Sprite::Gear(_)),
@@ -30,7 +30,16 @@ impl Pool {
             items.push(sprite);
         }
 
-        Self { items }
+        // Every locked location takes one item out of the shuffle
+        for (id, item) in &plando.placements {
+            let index = items
+                .iter()
+                .position(|sprite| *sprite == item.0)
+                .ok_or_else(|| format!("no {item} left in the pool to place at {id}"))?;
+            items.swap_remove(index);
+        }
+
+        Ok(Self { items })
     }
 
     pub fn contains_all(&self, items: &[Sprite]) -> bool {
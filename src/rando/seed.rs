@@ -2,18 +2,25 @@ use std::ops::{Deref, DerefMut};
 
 use crate::map::{Map, SpriteData};
 
-use super::id::Id;
+use super::{
+    id::{FindId, Id},
+    RandoError,
+};
 
 #[derive(Debug, PartialEq, Eq, Default)]
 pub struct Seed {
     pub placements: Vec<(Id, SpriteData)>,
+    /// Position to start at, if not the vanilla one
+    pub start: Option<Id>,
 }
 
 impl Seed {
-    pub fn apply(self, maps: &mut [Map]) {
-        for (location, sprite) in self.placements {
-            *location.expect_sprite_mut(maps) = sprite;
+    pub fn apply(&self, maps: &mut [Map]) -> Result<(), RandoError> {
+        for (location, sprite) in &self.placements {
+            *location.find_sprite_mut(maps)? = sprite.clone();
         }
+
+        Ok(())
     }
 }
 
@@ -0,0 +1,204 @@
+use std::{ffi::OsStr, path::Path};
+
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helpers::file_open,
+    map::{Gear, Map},
+    Result,
+};
+
+use super::Plando;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Game mode to randomize
+    pub mode: GameMode,
+    /// Start in a random area instead of Spawn, which needs the position to be written into an existing save
+    pub random_start: bool,
+    /// Shuffle map transfer destinations
+    pub transfers: bool,
+    /// How transfer destinations are shuffled
+    pub transfer_mode: TransferMode,
+    /// Shuffle Gold, Silver and Boulder doors
+    pub doors: bool,
+    /// Shuffle enemies
+    pub enemies: bool,
+    /// How enemies are shuffled
+    pub enemy_mode: EnemyMode,
+    /// Shuffle gear and collectible locations
+    pub items: bool,
+    /// How items are placed
+    pub fill_algorithm: FillAlgorithm,
+    /// Which spheres gear gets placed in
+    pub balancing: Balancing,
+    /// How keys are accounted for when opening doors
+    pub key_logic: KeyLogic,
+    /// Which tricks from the logic file may be required
+    pub tricks: Tricks,
+    /// Changes to the look and sound of maps, which don't affect logic
+    pub cosmetics: Cosmetics,
+    /// Fixed item placements and starting inventory
+    pub plando: Plando,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            random_start: false,
+            transfers: true,
+            transfer_mode: TransferMode::default(),
+            doors: true,
+            enemies: true,
+            enemy_mode: EnemyMode::default(),
+            items: true,
+            fill_algorithm: FillAlgorithm::default(),
+            balancing: Balancing::default(),
+            key_logic: KeyLogic::default(),
+            tricks: Tricks::default(),
+            cosmetics: Cosmetics::default(),
+            plando: Plando::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn parse<P: AsRef<Path>>(path: P) -> Result<Self> {
+        fn parse(path: &Path) -> Result<Settings> {
+            let file = file_open(path)?;
+
+            let settings = if path.extension() == Some(OsStr::new("json")) {
+                serde_json::from_reader(file)?
+            } else {
+                serde_yaml::from_reader(file)?
+            };
+
+            Ok(settings)
+        }
+
+        parse(path.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum GameMode {
+    /// Heroic Hardcore Mode
+    #[default]
+    Hardcore,
+    /// The main campaign
+    Main,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransferMode {
+    /// Transfers are shuffled in pairs, so taking a transfer back returns to where you came from
+    #[default]
+    Coupled,
+    /// Every transfer leads somewhere of its own, taking it back may lead elsewhere
+    Decoupled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnemyMode {
+    /// Enemies may end up anywhere, regardless of their strength
+    #[default]
+    Blind,
+    /// Enemies are placed so they can be defeated with the swords and gems reachable before them
+    Progressive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FillAlgorithm {
+    /// Items are drawn at random and placed into locations reachable without them, unreachable locations get filler
+    Forward,
+    /// All gear is placed first, each into a location reachable while assuming every item not yet placed is owned
+    ///
+    /// The default since it fails fewer attempts than forward fill
+    #[default]
+    Assumed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyLogic {
+    /// A door can be opened once one key of its color is reachable
+    #[default]
+    Simple,
+    /// Doors of a color are only considered open if there are enough keys for all of them that may be encountered
+    Counted,
+}
+
+/// Spheres here only count the rounds in which gear is collected, everything else is assumed to be owned
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Balancing {
+    /// Which spheres are preferred when placing gear
+    pub bias: SphereBias,
+    /// Earliest sphere a piece of gear may end up in
+    pub min_sphere: IndexMap<Gear, usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SphereBias {
+    /// Every reachable location is equally likely, which favors spheres with many locations
+    #[default]
+    Off,
+    /// Every sphere is equally likely
+    Even,
+    /// Earlier spheres are more likely
+    Early,
+    /// Later spheres are more likely
+    Late,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tricks {
+    /// Enable every trick up to this difficulty
+    pub difficulty: Option<TrickDifficulty>,
+    /// Enable these tricks regardless of their difficulty
+    pub enabled: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrickDifficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Cosmetics {
+    /// Shuffle which color palettes maps use
+    pub palettes: bool,
+    /// Shuffle which music tracks maps play
+    pub music: bool,
+}
+
+impl GameMode {
+    pub fn contains(self, map: &Map) -> bool {
+        match self {
+            Self::Hardcore => map.is_hardcore(),
+            Self::Main => map.is_main_campaign(),
+        }
+    }
+
+    pub fn save_name(self) -> &'static str {
+        match self {
+            Self::Hardcore => "hcp",
+            Self::Main => "savedata",
+        }
+    }
+}
@@ -1,18 +1,362 @@
-use indexmap::IndexMap;
-use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+};
 
-use crate::map::Gear;
+use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-use super::id::Id;
+use crate::map::{Door, Gear, Map, Sprite};
+
+use super::{
+    id::{FindId, Id},
+    logic::{Logic, Reach},
+    pool::Pool,
+    RandoError, Settings,
+};
 
 #[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct Spoiler {
     pub seed: String,
+    pub code: String,
+    pub settings: Settings,
     #[serde(flatten)]
     pub items: ItemSpoiler,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Default)]
 pub struct ItemSpoiler {
+    pub start: StartSpoiler,
     pub gear: IndexMap<Gear, Id>,
+    pub transfers: Vec<TransferSpoiler>,
+    pub playthrough: Vec<IndexMap<Id, PlacementSpoiler>>,
+    /// Item locations which can't be reached, even with every item
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unreachable: Vec<Id>,
+    pub placements: IndexMap<Id, PlacementSpoiler>,
+    pub spheres: SphereStats,
+    /// Areas which have to be passed through to collect all gear, picking up the other items along the way
+    pub required_areas: Vec<String>,
+    /// Enabled tricks which are needed to collect all gear, along with their description
+    pub required_tricks: IndexMap<String, String>,
+}
+
+/// Gear spheres only count the rounds in which gear is collected, everything else is assumed to be owned
+#[derive(Debug, PartialEq, Eq, Serialize, Default)]
+pub struct SphereStats {
+    pub playthrough: usize,
+    pub gear_spheres: usize,
+    pub gear: IndexMap<Gear, usize>,
+    pub locations_per_gear_sphere: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct StartSpoiler {
+    pub area: String,
+    /// Only set if the starting position was moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Id>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TransferSpoiler {
+    pub from: Id,
+    pub from_area: String,
+    pub to: Id,
+    pub to_area: String,
+    pub two_way: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct PlacementSpoiler {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    pub sprite: String,
+}
+
+impl ItemSpoiler {
+    pub fn new(
+        maps: &[Map],
+        randomized: &[Map],
+        logic: &Logic,
+        reach: &Reach,
+        start_position: Option<Id>,
+    ) -> Result<Self, RandoError> {
+        let area_names = logic.area_names();
+        let placement = |id: Id, sprite: Sprite| PlacementSpoiler {
+            area: area_names.get(&id).map(|area| (*area).clone()),
+            sprite: sprite.name(),
+        };
+
+        let items = logic
+            .items()
+            .map(|id| Ok((id, id.find_sprite(randomized)?.kind.into())))
+            .collect::<Result<HashMap<Id, Sprite>, RandoError>>()?;
+
+        let gear: IndexMap<_, _> = logic
+            .items()
+            .filter_map(|id| match items[&id] {
+                Sprite::Gear(gear) => Some((gear, id)),
+                _ => None,
+            })
+            .collect();
+
+        let transfers = transfer_pairs(maps, randomized, logic, &area_names)?;
+
+        let playthrough: Vec<IndexMap<_, _>> = reach
+            .spheres(&items)?
+            .into_iter()
+            .map(|sphere| {
+                sphere
+                    .into_iter()
+                    .map(|id| (id, placement(id, items[&id])))
+                    .collect()
+            })
+            .collect();
+
+        let unreachable = logic
+            .items()
+            .filter(|id| !playthrough.iter().any(|sphere| sphere.contains_key(id)))
+            .collect();
+
+        let mut filler = Pool::default();
+        filler.extend(
+            logic
+                .items()
+                .map(|id| items[&id])
+                .filter(|sprite| !matches!(sprite, Sprite::Gear(_))),
+        );
+        let gear_placements = gear
+            .iter()
+            .map(|(gear, id)| (*id, Sprite::Gear(*gear)))
+            .collect();
+
+        let spheres = SphereStats::new(
+            &items,
+            &gear,
+            playthrough.len(),
+            &reach.location_spheres(&gear_placements, &filler)?,
+        );
+        let gear_ids = gear.values().copied().collect();
+        let required_areas = reach
+            .required_areas(&items, &gear_ids)?
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let required_tricks = reach
+            .required_tricks(&items, &gear_ids)?
+            .into_iter()
+            .map(|name| (name.to_string(), logic.tricks[name].description.clone()))
+            .collect();
+
+        let placements = randomized
+            .iter()
+            .flat_map(|map| {
+                map.sprites_with_positions()
+                    .map(|(x, y, sprite)| (Id::new(map.identifier, x, y), sprite.kind.into()))
+            })
+            .filter(|(id, sprite)| {
+                items.contains_key(id)
+                    || matches!(
+                        sprite,
+                        Sprite::Door(Door::Gold | Door::Silver | Door::Boulder) | Sprite::Enemy(_)
+                    )
+            })
+            .map(|(id, sprite)| (id, placement(id, sprite)))
+            .collect();
+
+        let start = StartSpoiler {
+            area: reach.start().to_string(),
+            position: start_position,
+        };
+
+        Ok(Self {
+            start,
+            gear,
+            transfers,
+            playthrough,
+            unreachable,
+            placements,
+            spheres,
+            required_areas,
+            required_tricks,
+        })
+    }
+}
+
+impl SphereStats {
+    fn new(
+        items: &HashMap<Id, Sprite>,
+        gear: &IndexMap<Gear, Id>,
+        playthrough: usize,
+        spheres: &[IndexSet<Id>],
+    ) -> Self {
+        let sphere_of = |id: &Id| spheres.iter().position(|sphere| sphere.contains(id));
+
+        Self {
+            playthrough,
+            gear_spheres: spheres.len(),
+            gear: gear
+                .iter()
+                .filter_map(|(gear, id)| Some((*gear, sphere_of(id)?)))
+                .collect(),
+            locations_per_gear_sphere: spheres
+                .iter()
+                .map(|sphere| sphere.iter().filter(|id| items.contains_key(id)).count())
+                .collect(),
+        }
+    }
+}
+
+fn transfer_pairs(
+    maps: &[Map],
+    randomized: &[Map],
+    logic: &Logic,
+    area_names: &HashMap<Id, &String>,
+) -> Result<Vec<TransferSpoiler>, RandoError> {
+    let mut links = vec![];
+    for (from_area, from) in logic.transfer_groups()?.into_iter().flatten() {
+        let sprite = from.find_sprite(randomized)?;
+        if let Some(to) = sprite.destination_transfer(maps) {
+            links.push((from_area, from, to));
+        }
+    }
+
+    let destinations = links
+        .iter()
+        .map(|(_, from, to)| (*from, *to))
+        .collect::<HashSet<_>>();
+
+    let mut listed = HashSet::new();
+    let mut transfers = vec![];
+
+    for (from_area, from, to) in links {
+        if listed.contains(&(to, from)) {
+            continue;
+        }
+        listed.insert((from, to));
+
+        let to_area = area_names
+            .get(&to)
+            .map_or_else(|| "Unknown".to_string(), |area| (*area).clone());
+
+        transfers.push(TransferSpoiler {
+            from,
+            from_area: from_area.clone(),
+            to,
+            to_area,
+            two_way: destinations.contains(&(to, from)),
+        });
+    }
+
+    Ok(transfers)
+}
+
+impl Display for Spoiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f, "Code: {}", self.code)?;
+
+        writeln!(f, "\nSettings:")?;
+        let settings = serde_yaml::to_string(&self.settings).map_err(|_| fmt::Error)?;
+        for line in settings.lines() {
+            writeln!(f, "  {line}")?;
+        }
+
+        write!(f, "{}", self.items)
+    }
+}
+
+impl Display for ItemSpoiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.start.position {
+            None => writeln!(f, "\nStart: {}", self.start.area)?,
+            Some(position) => writeln!(f, "\nStart: {} ({position})", self.start.area)?,
+        }
+
+        writeln!(f, "\nGear:")?;
+        for (gear, id) in &self.gear {
+            let area = self.placements.get(id).and_then(|p| p.area.as_deref());
+            writeln!(f, "  {gear:?}: {} ({id})", area.unwrap_or("Unknown"))?;
+        }
+
+        let spheres = &self.spheres;
+        writeln!(f, "\nSpheres:")?;
+        writeln!(f, "  Playthrough: {}", spheres.playthrough)?;
+        writeln!(f, "  Gear spheres: {}", spheres.gear_spheres)?;
+        for (index, locations) in spheres.locations_per_gear_sphere.iter().enumerate() {
+            let gear = spheres
+                .gear
+                .iter()
+                .filter(|(_, sphere)| **sphere == index)
+                .map(|(gear, _)| format!("{gear:?}"))
+                .join(", ");
+            if gear.is_empty() {
+                writeln!(f, "    Sphere {index}: {locations} locations")?;
+            } else {
+                writeln!(f, "    Sphere {index}: {locations} locations, {gear}")?;
+            }
+        }
+
+        writeln!(f, "\nRequired areas:")?;
+        for area in &self.required_areas {
+            writeln!(f, "  {area}")?;
+        }
+
+        if !self.required_tricks.is_empty() {
+            writeln!(f, "\nRequired tricks:")?;
+            for (trick, description) in &self.required_tricks {
+                if description.is_empty() {
+                    writeln!(f, "  {trick}")?;
+                } else {
+                    writeln!(f, "  {trick}: {description}")?;
+                }
+            }
+        }
+
+        writeln!(f, "\nTransfers:")?;
+        for transfer in &self.transfers {
+            let arrow = if transfer.two_way { "<->" } else { "->" };
+            writeln!(
+                f,
+                "  {} ({}) {arrow} {} ({})",
+                transfer.from_area, transfer.from, transfer.to_area, transfer.to
+            )?;
+        }
+
+        writeln!(f, "\nPlaythrough:")?;
+        for (index, sphere) in self.playthrough.iter().enumerate() {
+            writeln!(f, "  Sphere {index}:")?;
+            for (id, placement) in sphere {
+                writeln!(f, "    {placement} ({id})")?;
+            }
+        }
+
+        if !self.unreachable.is_empty() {
+            writeln!(f, "\nUnreachable:")?;
+            for id in &self.unreachable {
+                match self.placements.get(id) {
+                    Some(placement) => writeln!(f, "  {placement} ({id})")?,
+                    None => writeln!(f, "  {id}")?,
+                }
+            }
+        }
+
+        writeln!(f, "\nPlacements:")?;
+        for (id, placement) in &self.placements {
+            writeln!(f, "  {id}: {placement}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for PlacementSpoiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.area {
+            None => write!(f, "{}", self.sprite),
+            Some(area) => write!(f, "{} in {area}", self.sprite),
+        }
+    }
 }
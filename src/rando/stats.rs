@@ -0,0 +1,237 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display},
+};
+
+use indexmap::IndexMap;
+use itertools::Itertools;
+
+use crate::{
+    map::{Gear, Map},
+    Result,
+};
+
+use super::{generate_attempts, id::Id, Logic, RandoError, Settings, Spoiler};
+
+/// Results of generating many seeds with the same settings
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub seeds: usize,
+    /// Seeds which could not be generated, along with the last error
+    pub failures: Vec<(String, String)>,
+    /// How many seeds needed how many attempts
+    pub attempts: BTreeMap<u8, usize>,
+    /// How often each piece of gear ended up at each location
+    pub gear: IndexMap<Gear, HashMap<Id, usize>>,
+    /// How often each area had to be passed through to collect all gear
+    pub required_areas: HashMap<String, usize>,
+    playthrough_spheres: usize,
+    gear_spheres: usize,
+}
+
+impl Stats {
+    /// Generates the seeds "0" up to `seeds`, so the results are reproducible
+    pub fn collect(maps: &[Map], logic: &Logic, settings: &Settings, seeds: usize) -> Result<Self> {
+        let mut stats = Self::default();
+
+        for index in 0..seeds {
+            let name = index.to_string();
+            let attempts = generate_attempts(maps, logic, settings, Some(name.clone()));
+
+            stats.seeds += 1;
+            *stats.attempts.entry(attempts.count).or_default() += 1;
+
+            match attempts.result {
+                Ok((_, spoiler)) => stats.record(&spoiler),
+                // Every other seed would fail the same way
+                Err(err) if err.is::<RandoError>() => return Err(err),
+                Err(err) => stats.failures.push((name, err.to_string())),
+            }
+        }
+
+        Ok(stats)
+    }
+
+    fn record(&mut self, spoiler: &Spoiler) {
+        for (gear, id) in &spoiler.items.gear {
+            *self.gear.entry(*gear).or_default().entry(*id).or_default() += 1;
+        }
+
+        self.playthrough_spheres += spoiler.items.spheres.playthrough;
+        self.gear_spheres += spoiler.items.spheres.gear_spheres;
+
+        for area in &spoiler.items.required_areas {
+            *self.required_areas.entry(area.clone()).or_default() += 1;
+        }
+    }
+
+    pub fn successes(&self) -> usize {
+        self.seeds - self.failures.len()
+    }
+
+    pub fn failure_rate(&self) -> Option<f32> {
+        ratio(self.failures.len(), self.seeds)
+    }
+
+    /// Share of all attempts made that failed, including the retries of seeds that succeeded eventually
+    pub fn attempt_failure_rate(&self) -> Option<f32> {
+        let total = self
+            .attempts
+            .iter()
+            .map(|(attempts, seeds)| usize::from(*attempts) * seeds)
+            .sum::<usize>();
+
+        ratio(total - self.successes(), total)
+    }
+
+    pub fn average_playthrough_spheres(&self) -> Option<f32> {
+        ratio(self.playthrough_spheres, self.successes())
+    }
+
+    pub fn average_gear_spheres(&self) -> Option<f32> {
+        ratio(self.gear_spheres, self.successes())
+    }
+
+    /// Share of the successful seeds which required passing through `area`
+    pub fn required_area_share(&self, area: &str) -> Option<f32> {
+        ratio(
+            self.required_areas.get(area).copied().unwrap_or_default(),
+            self.successes(),
+        )
+    }
+}
+
+/// `None` if there is nothing to divide by, such as no seeds or no successful ones to average over
+fn ratio(count: usize, total: usize) -> Option<f32> {
+    (total > 0).then(|| count as f32 / total as f32)
+}
+
+fn percent(ratio: Option<f32>) -> String {
+    ratio.map_or_else(|| "-".to_string(), |ratio| format!("{:.1}%", ratio * 100.))
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Seeds: {}", self.seeds)?;
+        writeln!(
+            f,
+            "Failures: {} ({})",
+            self.failures.len(),
+            percent(self.failure_rate())
+        )?;
+        writeln!(
+            f,
+            "Failed attempts: {}",
+            percent(self.attempt_failure_rate())
+        )?;
+
+        writeln!(f, "\nAttempts:")?;
+        for (attempts, seeds) in &self.attempts {
+            writeln!(f, "  {attempts}: {seeds} seeds")?;
+        }
+
+        if let (Some(playthrough), Some(gear)) = (
+            self.average_playthrough_spheres(),
+            self.average_gear_spheres(),
+        ) {
+            writeln!(f, "\nAverage spheres:")?;
+            writeln!(f, "  Playthrough: {playthrough:.2}")?;
+            writeln!(f, "  Gear: {gear:.2}")?;
+        }
+
+        writeln!(f, "\nRequired areas:")?;
+        for (area, count) in self
+            .required_areas
+            .iter()
+            .sorted_unstable_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)))
+        {
+            let share = percent(self.required_area_share(area));
+            writeln!(f, "  {area}: {count} ({share})")?;
+        }
+
+        writeln!(f, "\nGear:")?;
+        for (gear, locations) in &self.gear {
+            let (id, count) = locations
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .unwrap();
+            writeln!(
+                f,
+                "  {gear:?}: {} locations, most often {id} ({count})",
+                locations.len()
+            )?;
+        }
+
+        if !self.failures.is_empty() {
+            writeln!(f, "\nFailures:")?;
+            for (seed, err) in &self.failures {
+                writeln!(f, "  {seed}: {err}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rando::{FillAlgorithm, GameMode},
+        rom::RomReader,
+    };
+
+    use super::*;
+
+    fn hardcore_logic() -> (Vec<Map>, Logic) {
+        let mut rom = RomReader::open("Roms/main.hsrom".into()).unwrap();
+        let mut maps = Map::parse_all(&mut rom).unwrap();
+        maps.retain(Map::is_hardcore);
+        let mut logic = Logic::parse("rando/logic.json", GameMode::Hardcore).unwrap();
+        logic.purge_doors(&maps).unwrap();
+
+        (maps, logic)
+    }
+
+    #[test]
+    fn hardcore_stats() {
+        let (maps, logic) = hardcore_logic();
+
+        let stats = Stats::collect(&maps, &logic, &Settings::default(), 10).unwrap();
+
+        assert_eq!(stats.seeds, 10);
+        assert_eq!(stats.attempts.values().sum::<usize>(), 10);
+        for locations in stats.gear.values() {
+            assert_eq!(locations.values().sum::<usize>(), stats.successes());
+        }
+    }
+
+    // Assumed fill is the default because of this
+    #[test]
+    fn assumed_fill_fails_less() {
+        let (maps, logic) = hardcore_logic();
+
+        let attempt_failure_rate = |fill_algorithm| {
+            let settings = Settings {
+                fill_algorithm,
+                ..Settings::default()
+            };
+            let stats = Stats::collect(&maps, &logic, &settings, 100).unwrap();
+            stats.attempt_failure_rate().unwrap()
+        };
+
+        assert!(
+            attempt_failure_rate(FillAlgorithm::Assumed)
+                <= attempt_failure_rate(FillAlgorithm::Forward)
+        );
+    }
+
+    #[test]
+    fn no_seeds() {
+        let stats = Stats::default();
+
+        assert_eq!(stats.failure_rate(), None);
+        assert_eq!(stats.attempt_failure_rate(), None);
+        assert_eq!(stats.average_playthrough_spheres(), None);
+        assert!(!stats.to_string().contains("NaN"));
+    }
+}
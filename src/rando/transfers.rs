@@ -1,14 +1,20 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, result::Result as StdResult};
 
-use rand::Rng;
+use indexmap::IndexMap;
+use rand::{seq::SliceRandom, Rng};
 use rand_pcg::Pcg64Mcg;
 
 use crate::{
     helpers::RemoveRandom,
-    map::{Map, Sprite, SpriteData, Things},
+    map::{Map, SpriteData},
+    Result,
 };
 
-use super::{id::Id, logic::Logic, Seed};
+use super::{
+    id::{FindId, Id},
+    logic::Logic,
+    RandoError, Seed,
+};
 
 impl Seed {
     pub fn generate_transfers<'logic>(
@@ -16,8 +22,8 @@ impl Seed {
         maps: &[Map],
         logic: &'logic Logic,
         rng: &mut Pcg64Mcg,
-    ) -> HashMap<Id, &'logic String> {
-        let mut groups = logic.transfer_groups();
+    ) -> Result<HashMap<Id, &'logic String>> {
+        let mut groups = logic.transfer_groups()?;
         let mut ends = vec![];
 
         groups.retain_mut(|group| {
@@ -34,15 +40,64 @@ impl Seed {
 
         while let Some(source) = choose_source(&mut groups, &mut ends, rng) {
             let target = choose_target(&mut groups, &mut ends, rng)
-                .expect("unable to match up all transfers");
+                .ok_or("unable to match up all transfers")?;
 
             logic_transfers.insert(source.1, target.0);
             logic_transfers.insert(target.1, source.0);
-            self.push((source.1, target.1.find_target_transfer(maps)));
-            self.push((target.1, source.1.find_target_transfer(maps)));
+            self.push((source.1, target.1.find_target_transfer(maps)?));
+            self.push((target.1, source.1.find_target_transfer(maps)?));
+        }
+
+        Ok(logic_transfers)
+    }
+
+    pub fn generate_decoupled_transfers<'logic>(
+        &mut self,
+        maps: &[Map],
+        logic: &'logic Logic,
+        rng: &mut Pcg64Mcg,
+    ) -> Result<HashMap<Id, &'logic String>> {
+        let mut groups = logic.transfer_groups()?;
+        if groups.len() < 2 {
+            Err(RandoError::TooFewTransferGroups)?;
+        }
+        groups.shuffle(rng);
+
+        let mut sources = groups.clone();
+        let mut targets = groups;
+        let mut links = vec![];
+
+        // Linking the groups in a cycle keeps every group reachable from every other
+        for (index, group) in sources.iter_mut().enumerate() {
+            let next = (index + 1) % targets.len();
+            let source = group.choose_remove(rng);
+            let target = targets[next].choose_remove(rng);
+            links.push((source, target));
+        }
+
+        let sources = sources.into_iter().flatten().collect::<Vec<_>>();
+        let mut targets = targets.into_iter().flatten().collect::<Vec<_>>();
+        targets.shuffle(rng);
+
+        // Swap targets of transfers leading into themselves until there are none left
+        let len = targets.len();
+        while let Some(index) = (0..len).find(|index| sources[*index].1 == targets[*index].1) {
+            if len < 2 {
+                Err("the only transfer left would lead into itself")?;
+            }
+            targets.swap(index, (index + 1) % len);
+        }
+
+        links.extend(sources.into_iter().zip(targets));
+
+        let mut logic_transfers = HashMap::new();
+
+        for (source, target) in links {
+            logic_transfers.insert(source.1, target.0);
+            self.push((source.1, target.1.find_target_transfer(maps)?));
         }
 
-        logic_transfers
+        Ok(logic_transfers)
     }
 }
 
@@ -86,34 +141,103 @@ fn choose_target<'a>(
     Some(target)
 }
 
-impl Id {
-    fn find_target_transfer(self, maps: &[Map]) -> SpriteData {
-        fn transfer_at(map: &Map, x: usize, y: usize) -> Option<&SpriteData> {
-            map.sprite(x, y).and_then(|sprite| {
-                matches!(sprite.kind.into(), Sprite::Things(Things::Transfer)).then_some(sprite)
-            })
+pub fn vanilla_transfers<'logic>(
+    maps: &[Map],
+    logic: &'logic Logic,
+) -> Result<HashMap<Id, &'logic String>> {
+    let transfer_areas = logic
+        .transfer_groups()?
+        .into_iter()
+        .flatten()
+        .map(|(area, transfer)| (transfer, area))
+        .collect::<HashMap<_, _>>();
+
+    let mut transfers = HashMap::new();
+
+    for transfer in transfer_areas.keys() {
+        let target = transfer.target_transfer(maps)?;
+        if let Some(target_area) = transfer_areas.get(&target) {
+            transfers.insert(*transfer, *target_area);
+        }
+    }
+
+    Ok(transfers)
+}
+
+/// Where taking a vanilla transfer arrives, one position for every area that can be arrived in
+pub fn start_positions<'logic>(
+    maps: &[Map],
+    logic: &'logic Logic,
+) -> Result<IndexMap<&'logic String, Id>> {
+    let transfers = logic.transfer_groups()?.into_iter().flatten();
+    let transfer_areas = transfers
+        .clone()
+        .map(|(area, transfer)| (transfer, area))
+        .collect::<HashMap<_, _>>();
+
+    let mut positions = IndexMap::new();
+
+    for (_, transfer) in transfers {
+        let target = transfer.target_transfer(maps)?;
+        if let Some(target_area) = transfer_areas.get(&target) {
+            let position = transfer.find_sprite(maps)?.destination();
+            positions.entry(*target_area).or_insert(position);
         }
+    }
 
-        let sprite = self.expect_sprite(maps);
-
-        debug_assert!(matches!(
-            sprite.kind.into(),
-            Sprite::Things(Things::Transfer)
-        ));
-
-        let x = sprite.extra_bytes[1] as usize;
-        let y = sprite.extra_bytes[2] as usize;
-        let target_id = Id::new(sprite.extra_bytes[0], x, y);
-        let map = target_id.expect_map(maps);
-
-        x.checked_sub(1)
-            .and_then(|x| transfer_at(map, x, y))
-            .or_else(|| x.checked_add(1).and_then(|x| transfer_at(map, x, y)))
-            .or_else(|| y.checked_sub(1).and_then(|y| transfer_at(map, x, y)))
-            .or_else(|| y.checked_add(1).and_then(|y| transfer_at(map, x, y)))
-            .unwrap_or_else(|| {
-                panic!("failed to find target transfer for {self} (targets {target_id})")
+    Ok(positions)
+}
+
+trait TargetTransfer {
+    fn find_target_transfer(self, maps: &[Map]) -> StdResult<SpriteData, RandoError>;
+    fn target_transfer(self, maps: &[Map]) -> StdResult<Id, RandoError>;
+}
+
+impl TargetTransfer for Id {
+    fn find_target_transfer(self, maps: &[Map]) -> StdResult<SpriteData, RandoError> {
+        Ok(self.target_transfer(maps)?.find_sprite(maps)?.clone())
+    }
+
+    fn target_transfer(self, maps: &[Map]) -> StdResult<Id, RandoError> {
+        let sprite = self.find_sprite(maps)?;
+
+        if !sprite.is_transfer() {
+            Err(RandoError::UnexpectedSprite {
+                id: self,
+                sprite: sprite.kind.into(),
+                expected: "transfer",
+            })?;
+        }
+
+        sprite
+            .destination_transfer(maps)
+            .ok_or_else(|| RandoError::MissingTargetTransfer {
+                transfer: self,
+                destination: sprite.destination(),
             })
-            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_transfer_group() {
+        let logic = serde_json::from_str::<Logic>(
+            r#"{"areas": {
+                "Spawn": {"paths": {"Other": [[]]}, "transfers": ["1.2.3"]},
+                "Other": {"transfers": ["1.4.5"]}
+            }}"#,
+        )
+        .unwrap();
+
+        let error = Seed::default()
+            .generate_decoupled_transfers(&[], &logic, &mut Pcg64Mcg::new(0))
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<RandoError>(),
+            Some(&RandoError::TooFewTransferGroups)
+        );
     }
 }
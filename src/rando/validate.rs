@@ -0,0 +1,271 @@
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
+use rand_pcg::Pcg64Mcg;
+use serde::Serialize;
+
+use crate::{
+    map::{Door, Map, Sprite, SpriteData, Things},
+    Result,
+};
+
+use super::{
+    generator::Generator,
+    id::Id,
+    logic::{Logic, Requirement},
+    Settings,
+};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub area: Option<String>,
+    pub id: Option<Id>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(area: Option<&String>, id: Option<Id>, message: impl Into<String>) -> Self {
+        Self {
+            area: area.cloned(),
+            id,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(area) = &self.area {
+            write!(f, "{area}: ")?;
+        }
+        if let Some(id) = self.id {
+            write!(f, "{id}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Check the logic against the maps of its game mode
+///
+/// If no problems are found, this also attempts to set up a generator, which will purge doors from the logic
+pub fn validate_logic(logic: &mut Logic, maps: &[Map]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    check_areas(logic, &mut diagnostics);
+    check_ids(logic, maps, &mut diagnostics);
+    check_unmapped(logic, maps, &mut diagnostics);
+
+    if diagnostics.is_empty() {
+        if let Err(err) = set_up_generator(logic, maps) {
+            diagnostics.push(Diagnostic::new(
+                None,
+                None,
+                format!("failed to set up generator: {err}"),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+fn set_up_generator(logic: &mut Logic, maps: &[Map]) -> Result<()> {
+    logic.purge_doors(maps)?;
+
+    let settings = Settings {
+        mode: logic.mode,
+        ..Settings::default()
+    };
+    Generator::new(maps, logic, &settings, &mut Pcg64Mcg::new(0))?;
+
+    Ok(())
+}
+
+fn check_areas(logic: &Logic, diagnostics: &mut Vec<Diagnostic>) {
+    for (name, area) in &logic.areas {
+        let mut ids = area.items.keys().chain(&area.transfers);
+        match ids.next() {
+            None => diagnostics.push(Diagnostic::new(
+                Some(name),
+                None,
+                "area has no items or transfers",
+            )),
+            Some(first) => {
+                for id in ids {
+                    if id.map != first.map {
+                        diagnostics.push(Diagnostic::new(
+                            Some(name),
+                            Some(*id),
+                            format!("area contains items or transfers in multiple maps, {first} is in another map"),
+                        ));
+                    }
+                }
+            }
+        }
+
+        for path in area.paths.keys() {
+            match logic.areas.get(path) {
+                None => diagnostics.push(Diagnostic::new(
+                    Some(name),
+                    None,
+                    format!("path target \"{path}\" doesn't exist"),
+                )),
+                Some(target) if !target.paths.contains_key(name) => {
+                    diagnostics.push(Diagnostic::new(
+                        Some(name),
+                        None,
+                        format!("{name} -> {path} exists but {path} -> {name} doesn't"),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+fn check_ids(logic: &Logic, maps: &[Map], diagnostics: &mut Vec<Diagnostic>) {
+    for (name, area) in &logic.areas {
+        for id in area.items.keys() {
+            match sprite(*id, maps).map(|sprite| sprite.kind.into()) {
+                Some(Sprite::Collectible(_) | Sprite::Gear(_) | Sprite::Door(_)) => {}
+                Some(other) => diagnostics.push(Diagnostic::new(
+                    Some(name),
+                    Some(*id),
+                    format!("item is {other:?}, expected a collectible, gear or door"),
+                )),
+                None => diagnostics.push(Diagnostic::new(
+                    Some(name),
+                    Some(*id),
+                    "item doesn't match any sprite",
+                )),
+            }
+        }
+
+        for id in &area.transfers {
+            match sprite(*id, maps) {
+                Some(sprite) if sprite.is_transfer() => {
+                    if sprite.destination_transfer(maps).is_none() {
+                        diagnostics.push(Diagnostic::new(
+                            Some(name),
+                            Some(*id),
+                            "transfer destination has no adjacent transfer to lead back",
+                        ));
+                    }
+                }
+                _ => diagnostics.push(Diagnostic::new(
+                    Some(name),
+                    Some(*id),
+                    "transfer doesn't match any transfer sprite",
+                )),
+            }
+        }
+
+        let requirements = area
+            .items
+            .values()
+            .chain(area.paths.values())
+            .flatten()
+            .flatten();
+        check_requirement_ids(name, requirements, logic, maps, diagnostics);
+    }
+
+    for (name, requirements) in &logic.macros {
+        let name = format!("@{name}");
+        let requirements = requirements.iter().flatten();
+        check_requirement_ids(&name, requirements, logic, maps, diagnostics);
+    }
+}
+
+fn check_requirement_ids<'a>(
+    name: &String,
+    requirements: impl Iterator<Item = &'a Requirement>,
+    logic: &Logic,
+    maps: &[Map],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for requirement in requirements {
+        let Requirement::Id(id) = requirement else {
+            continue;
+        };
+
+        let known = logic.items().contains(id)
+            || sprite(*id, maps).is_some_and(|sprite| {
+                matches!(
+                    sprite.kind.into(),
+                    Sprite::Door(Door::Gold | Door::Silver | Door::Boulder) | Sprite::Enemy(_)
+                )
+            });
+        if !known {
+            diagnostics.push(Diagnostic::new(
+                Some(name),
+                Some(*id),
+                "requirement doesn't reference an item, door or enemy",
+            ));
+        }
+    }
+}
+
+fn sprite(id: Id, maps: &[Map]) -> Option<&SpriteData> {
+    maps.iter()
+        .find(|map| map.identifier == id.map)
+        .and_then(|map| map.sprite(id.x, id.y))
+}
+
+fn check_unmapped(logic: &Logic, maps: &[Map], diagnostics: &mut Vec<Diagnostic>) {
+    for map in maps {
+        if !logic.mode.contains(map) {
+            continue;
+        }
+
+        for (x, y, sprite) in map.sprites_with_positions() {
+            let id = Id::new(map.identifier, x, y);
+
+            if logic.is_excluded(id) {
+                continue;
+            }
+
+            let mapped = match sprite.kind.into() {
+                Sprite::Collectible(_) | Sprite::Gear(_) | Sprite::Door(_) => {
+                    logic.items().contains(&id)
+                }
+                Sprite::Things(Things::Transfer) => logic
+                    .areas
+                    .values()
+                    .flat_map(|area| &area.transfers)
+                    .contains(&id),
+                _ => true,
+            };
+
+            if !mapped {
+                diagnostics.push(Diagnostic::new(None, Some(id), "not mapped in logic"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rando::GameMode,
+        rom::{Rom, RomReader},
+    };
+
+    use super::*;
+
+    #[test]
+    fn hardcore_logic() {
+        let mut logic = Logic::parse("rando/logic.json", GameMode::Hardcore).unwrap();
+
+        let mut reader = RomReader::open("Roms/main.hsrom".into()).unwrap();
+        let rom = Rom::parse(&mut reader);
+        let mut maps = rom.maps.unwrap();
+        maps.retain(Map::is_hardcore);
+
+        let diagnostics = validate_logic(&mut logic, &maps);
+
+        assert!(
+            diagnostics.is_empty(),
+            "{}",
+            diagnostics.iter().format("\n")
+        );
+    }
+}
@@ -3,9 +3,12 @@ use std::collections::HashMap;
 use geo::{Centroid, Coord, CoordsIter, KNearestConcaveHull, Polygon};
 use image::{Rgba, RgbaImage};
 use imageproc::{
-    drawing::{draw_antialiased_line_segment_mut, draw_antialiased_polygon_mut},
+    drawing::{
+        draw_antialiased_line_segment_mut, draw_antialiased_polygon_mut, draw_hollow_rect_mut,
+    },
     pixelops::interpolate,
     point::Point,
+    rect::Rect,
 };
 use lyon::{
     algorithms::hatching::{HatchSegment, Hatcher, HatchingOptions, RegularHatchingPattern},
@@ -17,22 +20,24 @@ use lyon::{
     },
 };
 
-use super::{logic::Area, Logic};
+use super::{id::Id, logic::Area, Logic};
 
 #[allow(clippy::type_complexity)]
 pub struct Visualizer<'logic> {
     area_shapes: HashMap<u8, Vec<(&'logic Area, Polygon<f32>, geo::Point<f32>)>>,
     area_centroids: HashMap<&'logic String, geo::Point<f32>>,
+    empty_areas: Vec<&'logic String>,
 }
 
 impl<'logic> Visualizer<'logic> {
     pub fn new(logic: &'logic Logic) -> Self {
         let mut area_shapes = HashMap::<_, Vec<_>>::new();
         let mut area_centroids = HashMap::new();
+        let mut empty_areas = vec![];
 
         for (name, area) in &logic.areas {
             let Some(first_id) = area.items.keys().next().or_else(|| area.transfers.first()) else {
-                eprintln!("nothing in {name}");
+                empty_areas.push(name);
                 continue;
             };
 
@@ -59,9 +64,15 @@ impl<'logic> Visualizer<'logic> {
         Self {
             area_shapes,
             area_centroids,
+            empty_areas,
         }
     }
 
+    /// Areas without items or transfers, which have no shape to draw
+    pub fn empty_areas(&self) -> &[&'logic String] {
+        &self.empty_areas
+    }
+
     pub fn visualize_areas(&self, map: u8, image: &mut RgbaImage) {
         for (_, outline, _) in &self.area_shapes[&map] {
             if outline.coords_count() > 2 {
@@ -70,6 +81,48 @@ impl<'logic> Visualizer<'logic> {
         }
     }
 
+    /// Shades locations by how often they came up, relative to the most frequent one
+    pub fn visualize_frequencies(
+        &self,
+        map: u8,
+        frequencies: &HashMap<Id, usize>,
+        image: &mut RgbaImage,
+    ) {
+        let Some(max) = frequencies.values().max() else {
+            return;
+        };
+
+        for (id, count) in frequencies {
+            if id.map != map {
+                continue;
+            }
+
+            let weight = 0.25 + 0.75 * *count as f32 / *max as f32;
+            for y in id.y * 16..id.y * 16 + 16 {
+                for x in id.x * 16..id.x * 16 + 16 {
+                    let pixel = image.get_pixel_mut(x as u32, y as u32);
+                    *pixel = interpolate(Rgba::from([255, 0, 0, 255]), *pixel, weight);
+                }
+            }
+        }
+    }
+
+    pub fn visualize_start(&self, map: u8, position: Id, image: &mut RgbaImage) {
+        if position.map != map {
+            return;
+        }
+
+        let (x, y) = (position.x as i32 * 16, position.y as i32 * 16);
+        for inset in 0..2 {
+            draw_hollow_rect_mut(
+                image,
+                Rect::at(x + inset, y + inset)
+                    .of_size(16 - 2 * inset as u32, 16 - 2 * inset as u32),
+                Rgba::from([0, 255, 0, 255]),
+            );
+        }
+    }
+
     pub fn visualize_connections(&self, map: u8, image: &mut RgbaImage) {
         for (area, _, centroid) in &self.area_shapes[&map] {
             for path in area.paths.keys() {
@@ -2,12 +2,13 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, Read, Write};
 use std::path::PathBuf;
+use std::result::Result as StdResult;
 
 use zip::write::SimpleFileOptions;
 use zip::{ZipArchive, ZipWriter};
 
-use crate::graphics::TileData;
-use crate::helpers::{self, ResultExtension};
+use crate::graphics::{DrawData, TileData};
+use crate::helpers;
 use crate::map::{Map, MapColors, MapMeta};
 use crate::Result;
 
@@ -18,12 +19,12 @@ pub struct RomReader {
 }
 
 impl RomReader {
-    pub fn open(rom: PathBuf) -> Option<Self> {
-        let archive = helpers::file_open(rom)
-            .and_then(|file| Ok(ZipArchive::new(BufReader::new(file))?))
-            .ok_feedback("Read rom")?;
+    pub fn open(rom: PathBuf) -> Result<Self> {
+        let file = helpers::file_open(&rom)?;
+        let archive = ZipArchive::new(BufReader::new(file))
+            .map_err(|err| format!("Failed to read \"{}\": {err}", rom.display()))?;
         let index = Index::new(&archive);
-        Some(Self { archive, index })
+        Ok(Self { archive, index })
     }
 }
 
@@ -94,20 +95,23 @@ impl RomWriter {
     }
 }
 
+/// The parsed contents of a rom
+///
+/// Every part is parsed on its own, so one failing to parse doesn't prevent using the others.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Rom {
-    pub tile_data: Option<TileData>,
-    pub maps: Option<Vec<Map>>,
-    pub map_colors: Option<MapColors>,
-    pub map_meta: Option<HashMap<usize, MapMeta>>,
+    pub tile_data: StdResult<TileData, String>,
+    pub maps: StdResult<Vec<Map>, String>,
+    pub map_colors: StdResult<MapColors, String>,
+    pub map_meta: StdResult<HashMap<usize, MapMeta>, String>,
 }
 
 impl Rom {
     pub fn parse(rom: &mut RomReader) -> Self {
-        let tile_data = TileData::parse(rom).ok_feedback("Parse graphics");
-        let maps = Map::parse_all(rom).ok_feedback("Parse maps");
-        let map_colors = MapColors::parse(rom).ok_feedback("Parse map colors");
-        let map_meta = MapMeta::parse_all(rom).ok_feedback("Parse map meta");
+        let tile_data = TileData::parse(rom).map_err(|err| err.to_string());
+        let maps = Map::parse_all(rom).map_err(|err| err.to_string());
+        let map_colors = MapColors::parse(rom).map_err(|err| err.to_string());
+        let map_meta = MapMeta::parse_all(rom).map_err(|err| err.to_string());
 
         Self {
             tile_data,
@@ -116,4 +120,18 @@ impl Rom {
             map_meta,
         }
     }
+
+    pub fn draw_data(&self) -> Option<DrawData<'_>> {
+        if let (Ok(tile_data), Ok(map_colors), Ok(map_meta)) =
+            (&self.tile_data, &self.map_colors, &self.map_meta)
+        {
+            Some(DrawData {
+                tile_data,
+                map_colors,
+                map_meta,
+            })
+        } else {
+            None
+        }
+    }
 }
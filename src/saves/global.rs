@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -88,8 +89,8 @@ impl GlobalFlag {
     }
 }
 
-pub fn decode() -> Result<()> {
-    let data = helpers::read("global")?;
+pub fn decode(dir: &Path) -> Result<()> {
+    let data = helpers::read(dir.join("global"))?;
 
     let sequence = data[..10].iter().join("");
 
@@ -102,7 +103,7 @@ pub fn decode() -> Result<()> {
     let global_save = GlobalSave { sequence, flags };
     let out = serde_json::to_string_pretty(&global_save)?;
 
-    helpers::write("global.json", out)?;
+    helpers::write(dir.join("global.json"), out)?;
 
     Ok(())
 }
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    map::{Collectible, Gear, Sprite},
+    Result,
+};
+
 macro_rules! inventory_struct {
     (@start $field:ident, $($fields:ident),*) => {
         inventory_struct!(@acc $($fields),* -> $field: u8);
@@ -298,6 +303,52 @@ inventory! {
 }
 
 impl Inventory {
+    /// Set how many of an item are held, regardless of how many were held before
+    pub fn set_count(&mut self, sprite: Sprite, count: u8) -> Result<()> {
+        let counter = match sprite {
+            Sprite::Collectible(collectible) => match collectible {
+                Collectible::GoldKey => &mut self.gold_keys,
+                Collectible::SilverKey => &mut self.silver_keys,
+                Collectible::RedKey => &mut self.red_keys,
+                Collectible::GreenKey => &mut self.green_keys,
+                Collectible::BlueKey => &mut self.blue_keys,
+                Collectible::Sword => &mut self.swords,
+                Collectible::PortalStone => &mut self.portal_stones,
+                Collectible::Gem => &mut self.gems,
+                Collectible::Treasure => &mut self.treasures,
+                Collectible::GoldSword => &mut self.gold_sword,
+                Collectible::Heart => &mut self.hearts,
+                Collectible::Shield => &mut self.shield,
+                Collectible::Stopwatch => &mut self.stopwatch,
+                Collectible::GemHeart => &mut self.gem_heart,
+                Collectible::TealKey => &mut self.teal_keys,
+                Collectible::PurpleKey => &mut self.purple_keys,
+                Collectible::PossumCoin => &mut self.possum_coins,
+                Collectible::ShrineKey | Collectible::UnderworldKey => {
+                    Err(format!("setting {collectible:?} is not supported"))?
+                }
+            },
+            Sprite::Gear(gear) => match gear {
+                Gear::Hammer => &mut self.hammers,
+                Gear::Boots => &mut self.boots,
+                Gear::Compass => &mut self.compasses,
+                Gear::Spectacles => &mut self.spectacles,
+                Gear::LavaCharm => &mut self.lava_charms,
+                Gear::WindRing => &mut self.water_ring,
+                Gear::SkeletonKey => &mut self.skeleton_key,
+                Gear::GemSword => &mut self.gem_sword,
+                Gear::GemShield => &mut self.gem_shield,
+                Gear::RedSword => &mut self.red_sword,
+                Gear::RedShield => &mut self.red_shield,
+            },
+            other => Err(format!("{other:?} is not an inventory item"))?,
+        };
+
+        *counter = count;
+
+        Ok(())
+    }
+
     pub fn completion_column(&self) -> Vec<u8> {
         vec![
             self.bunny_crime_scene,
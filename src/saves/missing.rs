@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::map::{Collectible, Door, Map, Sprite};
 use crate::{helpers, Result};
@@ -6,7 +7,8 @@ use crate::{helpers, Result};
 use super::SavePretty;
 
 impl SavePretty {
-    pub fn check(&self, name: &str, maps: &[Map]) -> Result<()> {
+    /// Writes the items missing from this save into `completion_dir`
+    pub fn check(&self, name: &str, maps: &[Map], completion_dir: &Path) -> Result<()> {
         let mut missing = HashMap::<_, Vec<_>>::new();
 
         let ids = relevant_map_ids(name);
@@ -27,7 +29,7 @@ impl SavePretty {
         }
 
         let out = serde_json::to_string_pretty(&missing)?;
-        helpers::write(format!("completion/{name}_missing.json"), out)?;
+        helpers::write(completion_dir.join(format!("{name}_missing.json")), out)?;
 
         Ok(())
     }
@@ -36,45 +38,8 @@ impl SavePretty {
 fn relevant_map_ids(name: &str) -> &[u8] {
     match name {
         "bunny" => &[Map::HEROS_SPRINT],
-        "hcp" => &[
-            Map::HHM_CASTLE_GROUNDS,
-            Map::HHM_CASTLE_MONILLUD,
-            Map::HHM_STRANGE_AREA,
-            Map::HHM_THE_UNDERWORLD,
-        ],
-        "savedata" | "savedatb" | "savedatc" => &[
-            Map::DUST_SHELF,
-            Map::NORTH_MUNDEMAN,
-            Map::SOUTH_MUNDEMAN,
-            Map::VERDANT_COAST,
-            Map::OTHERWORLD_ARENA,
-            Map::CASTLE_GROUNDS,
-            Map::SANCTUARY,
-            Map::THE_TUNNELS,
-            Map::GLITCH,
-            Map::LUDDERSHORE,
-            Map::THE_TUNDRA,
-            Map::FROZEN_SHORE,
-            Map::HALLOW_GROUND,
-            Map::SOUTHERN_SWAMP,
-            Map::DRAGONS_LAIR,
-            Map::CORRUPTED_CASTLE,
-            Map::CASTLE_MONILLUD,
-            Map::THE_UNDERWORLD,
-            Map::OTHERWORLD,
-            Map::MOLTEN_CAVERN,
-            Map::THE_DUNGEONS,
-            Map::ITEM_SHOP,
-            Map::CONVERGENCE,
-            Map::TRIAL_OF_REALITY,
-            Map::FALLEN_WORLD,
-            Map::ROAD_TO_HELL,
-            Map::HAUNTED_MANSE,
-            Map::MOONWELL,
-            Map::BETWEEN_WORLDS,
-            Map::SMUGGLERS_ROAD,
-            Map::SMUGGLERS_RUIN,
-        ],
+        "hcp" => &Map::HEROIC_HARDCORE,
+        "savedata" | "savedatb" | "savedatc" => &Map::MAIN_CAMPAIGN,
         _ => panic!("unknown save name {name}"),
     }
 }
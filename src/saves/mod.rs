@@ -16,32 +16,37 @@ use serde::{Deserialize, Serialize};
 use strum::FromRepr;
 
 use crate::data::{DEOBF, OBF};
-use crate::helpers::{self, ResultExtension};
+use crate::helpers;
 use crate::Result;
 
+/// Each save is `None` if there is no such save file
 pub struct Saves {
-    pub savedata: Option<SavePretty>,
-    pub savedatb: Option<SavePretty>,
-    pub savedatc: Option<SavePretty>,
-    pub bunny: Option<SavePretty>,
-    pub hardcore: Option<SavePretty>,
+    pub savedata: Option<Result<SavePretty>>,
+    pub savedatb: Option<Result<SavePretty>>,
+    pub savedatc: Option<Result<SavePretty>>,
+    pub bunny: Option<Result<SavePretty>>,
+    pub hardcore: Option<Result<SavePretty>>,
+    pub global: Option<Result<()>>,
 }
 
 impl Saves {
-    pub fn decode() -> Self {
-        let savedata = decode("savedata", true).ok_feedback("Export savedata");
-        let savedatb = decode("savedatb", true).ok_feedback("Export savedatb");
-        let savedatc = decode("savedatc", true).ok_feedback("Export savedatc");
-        let bunny = decode("bunny", false).ok_feedback("Export bunny");
-        let hardcore = decode("hcp", false).ok_feedback("Export hardcore");
-        global::decode().feedback("Export global save");
+    /// Decodes the saves found in `dir` next to themselves, completion columns are written into `completion_dir`
+    ///
+    /// Every save is decoded on its own, so one failing doesn't prevent decoding the others.
+    pub fn decode(dir: &Path, completion_dir: &Path) -> Self {
+        let completion = Some(completion_dir);
+        let existing = |name| {
+            let path = dir.join(name);
+            path.is_file().then_some(path)
+        };
 
         Self {
-            savedata,
-            savedatb,
-            savedatc,
-            bunny,
-            hardcore,
+            savedata: existing("savedata").map(|path| decode(path, completion)),
+            savedatb: existing("savedatb").map(|path| decode(path, completion)),
+            savedatc: existing("savedatc").map(|path| decode(path, completion)),
+            bunny: existing("bunny").map(|path| decode(path, None)),
+            hardcore: existing("hcp").map(|path| decode(path, None)),
+            global: existing("global").map(|_| global::decode(dir)),
         }
     }
 }
@@ -111,31 +116,31 @@ fn flip(s: &mut String) {
 fn unscramble_piece(s: &mut String, seed: usize) {
     let len = s.len();
 
-    if (seed + 1) % 3 == 0 {
+    if (seed + 1).is_multiple_of(3) {
         flip(s);
     }
-    if seed % 3 == 0 && len == 8 {
+    if seed.is_multiple_of(3) && len == 8 {
         unshift(s, (seed + 5) % 7 + 1);
     }
-    if (seed + 2) % 3 == 0 {
+    if (seed + 2).is_multiple_of(3) {
         flip(s);
     }
-    if seed % 3 == 0 {
+    if seed.is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 2) % 3 == 0 && len == 8 {
+    if (seed + 2).is_multiple_of(3) && len == 8 {
         unshift(s, (seed + 3) % 7 + 1);
     }
-    if (seed + 1) % 3 == 0 {
+    if (seed + 1).is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 2) % 3 == 0 {
+    if (seed + 2).is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 1) % 3 == 0 && len == 8 {
+    if (seed + 1).is_multiple_of(3) && len == 8 {
         unshift(s, seed % 7 + 1);
     }
-    if seed % 3 == 0 {
+    if seed.is_multiple_of(3) {
         flip(s);
     }
 }
@@ -143,31 +148,31 @@ fn unscramble_piece(s: &mut String, seed: usize) {
 fn scramble_piece(s: &mut String, seed: usize) {
     let len = s.len();
 
-    if seed % 3 == 0 {
+    if seed.is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 1) % 3 == 0 && len == 8 {
+    if (seed + 1).is_multiple_of(3) && len == 8 {
         shift(s, seed % 7 + 1);
     }
-    if (seed + 2) % 3 == 0 {
+    if (seed + 2).is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 1) % 3 == 0 {
+    if (seed + 1).is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 2) % 3 == 0 && len == 8 {
+    if (seed + 2).is_multiple_of(3) && len == 8 {
         shift(s, (seed + 3) % 7 + 1);
     }
-    if seed % 3 == 0 {
+    if seed.is_multiple_of(3) {
         flip(s);
     }
-    if (seed + 2) % 3 == 0 {
+    if (seed + 2).is_multiple_of(3) {
         flip(s);
     }
-    if seed % 3 == 0 && len == 8 {
+    if seed.is_multiple_of(3) && len == 8 {
         shift(s, (seed + 5) % 7 + 1);
     }
-    if (seed + 1) % 3 == 0 {
+    if (seed + 1).is_multiple_of(3) {
         flip(s);
     }
 }
@@ -242,7 +247,44 @@ fn scramble(steps: usize, data: &SaveDat) -> Result<String> {
     Ok(second_iteration)
 }
 
-pub fn decode(path: &str, write_completion: bool) -> Result<SavePretty> {
+pub fn decode(path: impl AsRef<Path>, completion_dir: Option<&Path>) -> Result<SavePretty> {
+    let path = path.as_ref();
+    let pretty = read(path)?;
+
+    let out = serde_json::to_string_pretty(&pretty)?;
+    helpers::write(path.with_extension("json"), out)?;
+
+    if let Some(completion_dir) = completion_dir {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let completion_column = pretty.inventory.completion_column().into_iter().join("\n");
+        helpers::write(
+            completion_dir.join(format!("{name}_completion.txt")),
+            completion_column,
+        )?;
+    }
+
+    Ok(pretty)
+}
+
+pub fn encode(path: impl AsRef<Path>) -> Result<()> {
+    let read_path = path.as_ref().with_extension("json");
+    let data = helpers::read_to_string(&read_path)?;
+
+    write(path, serde_json::from_str(&data)?)
+}
+
+/// Modify a save file in place
+pub fn edit<P, F>(path: P, f: F) -> Result<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut SavePretty) -> Result<()>,
+{
+    let mut save = read(&path)?;
+    f(&mut save)?;
+    write(path, save)
+}
+
+fn read(path: impl AsRef<Path>) -> Result<SavePretty> {
     let data = helpers::read_to_string(path)?;
     let (steps, savedat) = unscramble(data)?;
     let SaveDat {
@@ -286,7 +328,7 @@ pub fn decode(path: &str, write_completion: bool) -> Result<SavePretty> {
         .collect();
     let inventory = Inventory::from(values);
 
-    let pretty = SavePretty {
+    Ok(SavePretty {
         steps,
         position,
         inventory,
@@ -296,26 +338,10 @@ pub fn decode(path: &str, write_completion: bool) -> Result<SavePretty> {
         deaths,
         kills,
         label,
-    };
-
-    let out = serde_json::to_string_pretty(&pretty)?;
-    helpers::write(format!("{path}.json"), out)?;
-
-    if write_completion {
-        let completion_column = pretty.inventory.completion_column().into_iter().join("\n");
-        helpers::write(
-            format!("completion/{path}_completion.txt"),
-            completion_column,
-        )?;
-    }
-
-    Ok(pretty)
+    })
 }
 
-pub fn encode(path: impl AsRef<Path>) -> Result<()> {
-    let read_path = path.as_ref().with_extension("json");
-    let data = helpers::read_to_string(&read_path)?;
-
+fn write(path: impl AsRef<Path>, save: SavePretty) -> Result<()> {
     let SavePretty {
         steps,
         position,
@@ -326,7 +352,7 @@ pub fn encode(path: impl AsRef<Path>) -> Result<()> {
         deaths,
         kills,
         label,
-    } = serde_json::from_str(&data)?;
+    } = save;
     let values = inventory
         .into_vec()
         .into_iter()